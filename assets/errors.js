@@ -0,0 +1,9 @@
+// Error responses of the htmx routes carry a fragment for the #errors
+// element of the page, see html_errors in src/api_error.rs. htmx drops the
+// bodies of error responses unless told to swap them.
+document.addEventListener("htmx:beforeSwap", (event) => {
+  if (event.detail.xhr.status >= 400) {
+    event.detail.shouldSwap = true;
+    event.detail.isError = false;
+  }
+});
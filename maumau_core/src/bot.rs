@@ -0,0 +1,426 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    card::{Card, Suit},
+    card_set::CardSet,
+    game::{Action, Game, TurnState},
+    player::PlayerDTO,
+    view::PlayerView,
+};
+
+/// Decides the moves of a computer player.
+pub trait Strategy {
+    /// Picks one of `actions`, the viable actions of the seat, which is never
+    /// empty.
+    fn choose_action(&mut self, view: &PlayerView, actions: &[Action]) -> Action;
+}
+
+/// The strategies a bot seat can be filled with.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BotKind {
+    Random,
+    Greedy,
+    MonteCarlo(Difficulty),
+}
+
+impl BotKind {
+    pub const ALL: [BotKind; 5] = [
+        BotKind::Random,
+        BotKind::Greedy,
+        BotKind::MonteCarlo(Difficulty::Easy),
+        BotKind::MonteCarlo(Difficulty::Medium),
+        BotKind::MonteCarlo(Difficulty::Hard),
+    ];
+
+    /// A fresh strategy of this kind. `seed` makes its choices reproducible,
+    /// up to the time limit of [`MonteCarloBot`]s.
+    pub fn strategy(self, seed: u64) -> Box<dyn Strategy + Send> {
+        match self {
+            BotKind::Random => Box::new(RandomBot::new(seed)),
+            BotKind::Greedy => Box::new(GreedyBot),
+            BotKind::MonteCarlo(difficulty) => {
+                Box::new(MonteCarloBot::new(difficulty.budget(), seed))
+            }
+        }
+    }
+}
+
+impl Display for BotKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BotKind::Random => write!(f, "Random"),
+            BotKind::Greedy => write!(f, "Greedy"),
+            BotKind::MonteCarlo(difficulty) => write!(f, "Monte Carlo ({})", difficulty),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// How long a [`MonteCarloBot`] of this difficulty thinks about a move.
+    pub fn budget(self) -> Budget {
+        match self {
+            Difficulty::Easy => Budget {
+                playouts: 100,
+                time: Duration::from_millis(100),
+            },
+            Difficulty::Medium => Budget {
+                playouts: 500,
+                time: Duration::from_millis(500),
+            },
+            Difficulty::Hard => Budget {
+                playouts: 2000,
+                time: Duration::from_secs(2),
+            },
+        }
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "Easy"),
+            Difficulty::Medium => write!(f, "Medium"),
+            Difficulty::Hard => write!(f, "Hard"),
+        }
+    }
+}
+
+/// Limits of a [`MonteCarloBot`] per move, whichever is reached first.
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    pub playouts: usize,
+    pub time: Duration,
+}
+
+/// Picks any viable action.
+pub struct RandomBot {
+    rng: StdRng,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for RandomBot {
+    fn choose_action(&mut self, _view: &PlayerView, actions: &[Action]) -> Action {
+        actions
+            .choose(&mut self.rng)
+            .expect("bots are only asked when they can act")
+            .clone()
+    }
+}
+
+/// Opponents with at most this many cards are pointed out in hints.
+const FEW_CARDS: usize = 2;
+
+/// A suggested action with the reason for it, worded for the player.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Hint {
+    pub action: Action,
+    pub rationale: String,
+}
+
+/// Always announces and challenges, plays whenever it can, preferring to get
+/// rid of many cards at once and to keep jacks for later, and wishes the
+/// suit it holds most of.
+pub struct GreedyBot;
+
+impl GreedyBot {
+    /// The action this bot takes in `view`, with the reason for it. `None`
+    /// if there are no `actions`.
+    pub fn suggest(view: &PlayerView, actions: &[Action]) -> Option<Hint> {
+        let action = Self::choose(view, actions)?;
+        Some(Hint {
+            action: action.clone(),
+            rationale: Self::rationale(view, action),
+        })
+    }
+
+    fn choose<'a>(view: &PlayerView, actions: &'a [Action]) -> Option<&'a Action> {
+        let priority = |action: &Action| match action {
+            Action::CallMau | Action::CallMauMau => 3,
+            Action::ChallengeMau(_) => 2,
+            Action::DecideSuit(suit) if *suit == Self::best_suit(view) => 1,
+            _ => 0,
+        };
+        actions
+            .iter()
+            .filter(|action| priority(action) > 0)
+            .max_by_key(|action| priority(action))
+            .or_else(|| {
+                actions
+                    .iter()
+                    .filter(|action| !action.played_cards().is_empty())
+                    .max_by_key(|action| Self::play_score(view, action.played_cards()))
+            })
+            .or_else(|| actions.first())
+    }
+
+    fn play_score(view: &PlayerView, card_ids: &[u8]) -> i32 {
+        let cards: Vec<Card> = card_ids
+            .iter()
+            .filter_map(|card_id| Card::try_from(*card_id).ok())
+            .collect();
+        let Some(top) = cards.last() else {
+            return i32::MIN;
+        };
+        let mut rest = view.hand;
+        for card in &cards {
+            rest.remove(card.id);
+        }
+        if rest.is_empty() {
+            return i32::MAX;
+        }
+        let mut score = 10 * cards.len() as i32;
+        if cards.iter().any(|card| view.rules.is_wish_card(card)) {
+            score -= 15;
+        }
+        if view.rules.is_penalty_card(top) || view.rules.is_skip_card(top) {
+            score += 5;
+        }
+        // keep playing after this turn: hold on to cards that follow the top
+        score + (rest & CardSet::of_suit(&top.suit)).len() as i32
+    }
+
+    fn best_suit(view: &PlayerView) -> Suit {
+        Suit::ALL
+            .into_iter()
+            .max_by_key(|suit| (view.hand & CardSet::of_suit(suit)).len())
+            .expect("there are four suits")
+    }
+
+    fn rationale(view: &PlayerView, action: &Action) -> String {
+        let name = |player_id: i64| {
+            view.opponent(player_id)
+                .map_or("they".to_owned(), |opponent| opponent.username.clone())
+        };
+        let top_card = view
+            .top_card()
+            .map_or("top card".to_owned(), |card| card.to_string());
+        match action {
+            Action::CallMau => "call Mau: you can get down to one card this turn".to_owned(),
+            Action::CallMauMau => "call Mau-Mau: you can play your last card this turn".to_owned(),
+            Action::ChallengeMau(player_id) => {
+                format!("challenge {}: they did not call Mau", name(*player_id))
+            }
+            Action::DecideSuit(suit) => {
+                let held = (view.hand & CardSet::of_suit(suit)).len();
+                format!("wish {}: you hold {} of them", suit, held)
+            }
+            Action::DrawCards(n) if view.constraints.pending_draw.is_some() => {
+                format!(
+                    "draw {}: you cannot pass the penalty on",
+                    count(*n as usize)
+                )
+            }
+            Action::DrawCards(_) => match &view.constraints.wished_suit {
+                Some(suit) => format!("draw a card: you hold no {}, the wished suit", suit),
+                None => format!("draw a card: nothing in your hand fits the {}", top_card),
+            },
+            Action::CannotPlay => "end your turn: the drawn card does not fit".to_owned(),
+            Action::PlayCard(_) | Action::PlayCards(_) => Self::play_rationale(view, action),
+            Action::ReshuffleDeck => String::new(),
+        }
+    }
+
+    fn play_rationale(view: &PlayerView, action: &Action) -> String {
+        let cards: Vec<Card> = action
+            .played_cards()
+            .iter()
+            .filter_map(|card_id| Card::try_from(*card_id).ok())
+            .collect();
+        let names: Vec<String> = cards.iter().map(|card| card.to_string()).collect();
+        let play = match names.split_last() {
+            Some((last, [])) => format!("play {}", last),
+            Some((last, rest)) => format!("play {} and {}", rest.join(", "), last),
+            None => "play".to_owned(),
+        };
+        let top = cards.last().expect("plays have at least one card");
+        let mut rest = view.hand;
+        for card in &cards {
+            rest.remove(card.id);
+        }
+        // the play has to be followed through to name who it affects: a
+        // reversing rank turns play around before its penalty or skip hits
+        let direction = if view.rules.is_reverse_card(top) && cards.len() % 2 == 1 {
+            view.constraints.direction.reversed()
+        } else {
+            view.constraints.direction
+        };
+        let turn_order = view.turn_order(direction);
+        let skips = if view.rules.is_skip_card(top) {
+            usize::from(view.rules.eight_skips.unwrap_or_default()) * cards.len()
+        } else {
+            0
+        };
+        let mut skipped: Vec<&PlayerDTO> = vec![];
+        for opponent in turn_order.iter().cycle().take(skips).flatten() {
+            if !skipped
+                .iter()
+                .any(|player| player.user_id == opponent.user_id)
+            {
+                skipped.push(opponent);
+            }
+        }
+        // only pointed out, the choice does not depend on it
+        let close_to_winning = |player: &PlayerDTO| {
+            Some(player.hand_size)
+                .filter(|hand_size| *hand_size <= FEW_CARDS)
+                .map(count)
+        };
+        let reason = if rest.is_empty() {
+            "you have no cards left after it".to_owned()
+        } else if cards.iter().any(|card| view.rules.is_wish_card(card)) {
+            "nothing else fits, and you get to wish a suit".to_owned()
+        } else if cards.len() > 1 {
+            format!("you get rid of {} cards at once", cards.len())
+        } else if let Some(Some(next)) = turn_order
+            .first()
+            .filter(|_| view.rules.is_penalty_card(top))
+        {
+            match close_to_winning(next) {
+                Some(cards_left) => format!(
+                    "{} has to draw or pass it on, with only {} left",
+                    next.username, cards_left
+                ),
+                None => format!("{} has to draw or pass it on", next.username),
+            }
+        } else if let [next] = skipped[..] {
+            match close_to_winning(next) {
+                Some(cards_left) => {
+                    format!(
+                        "it skips {}, who has only {} left",
+                        next.username, cards_left
+                    )
+                }
+                None => format!("it skips {}", next.username),
+            }
+        } else if let Some((last, others)) = skipped.split_last() {
+            let others: Vec<&str> = others
+                .iter()
+                .map(|player| player.username.as_str())
+                .collect();
+            format!("it skips {} and {}", others.join(", "), last.username)
+        } else if let Some(jack) = rest.iter().find(|card| view.rules.is_wish_card(card)) {
+            format!("keep the {} for when nothing else fits", jack)
+        } else {
+            Self::follow_up(&rest, top)
+        };
+        format!("{}: {}", play, reason)
+    }
+
+    fn follow_up(rest: &CardSet, top: &Card) -> String {
+        match (*rest & CardSet::of_suit(&top.suit)).len() {
+            0 => "it is the card that fits best".to_owned(),
+            1 => format!("you keep another card of {} to follow it", top.suit),
+            n => format!("you keep {} more cards of {} to follow it", n, top.suit),
+        }
+    }
+}
+
+/// "1 card" or "n cards".
+fn count(cards: usize) -> String {
+    match cards {
+        1 => "1 card".to_owned(),
+        n => format!("{} cards", n),
+    }
+}
+
+impl Strategy for GreedyBot {
+    fn choose_action(&mut self, view: &PlayerView, actions: &[Action]) -> Action {
+        Self::choose(view, actions)
+            .expect("bots are only asked when they can act")
+            .clone()
+    }
+}
+
+/// Actions a playout takes before it is called off, the seat then scores as
+/// if it lost.
+const MAX_PLAYOUT_ACTIONS: usize = 500;
+
+/// Plays every candidate action in many sampled games: the cards it has not
+/// seen are dealt to the other hands and the deck at random, then everyone
+/// plays on like a [`GreedyBot`]. Picks the action that won most often.
+pub struct MonteCarloBot {
+    budget: Budget,
+    rng: StdRng,
+}
+
+impl MonteCarloBot {
+    pub fn new(budget: Budget, seed: u64) -> Self {
+        Self {
+            budget,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Applies `action` in a sampled game and plays it to the end. Returns 1
+    /// if the seat wins, less the later it finishes, and 0 if it does not.
+    fn playout(&mut self, view: &PlayerView, action: &Action) -> f64 {
+        let mut game = Game::determinize(view, &mut self.rng);
+        if game.apply(view.player_id, action.clone()).is_err() {
+            return 0.0;
+        }
+        for _ in 0..MAX_PLAYOUT_ACTIONS {
+            if game.turn_state == TurnState::GameOver {
+                break;
+            }
+            let player_id = game.current_turn_player;
+            let Some(seat_view) = game.player_view(player_id) else {
+                break;
+            };
+            if seat_view.viable_actions.is_empty() {
+                break;
+            }
+            let action = GreedyBot.choose_action(&seat_view, &seat_view.viable_actions);
+            if game.apply(player_id, action).is_err() {
+                break;
+            }
+        }
+        match game.placements.iter().position(|id| *id == view.player_id) {
+            Some(place) => 1.0 - place as f64 / game.players.len() as f64,
+            None => 0.0,
+        }
+    }
+}
+
+impl Strategy for MonteCarloBot {
+    fn choose_action(&mut self, view: &PlayerView, actions: &[Action]) -> Action {
+        if actions.len() == 1 {
+            return actions[0].clone();
+        }
+        let started = Instant::now();
+        let mut totals = vec![0.0; actions.len()];
+        let mut playouts = vec![0usize; actions.len()];
+        for round in 0..self.budget.playouts {
+            if started.elapsed() >= self.budget.time {
+                break;
+            }
+            let index = round % actions.len();
+            totals[index] += self.playout(view, &actions[index]);
+            playouts[index] += 1;
+        }
+        let mean = |index: usize| totals[index] / playouts[index].max(1) as f64;
+        let best = (0..actions.len())
+            .max_by(|a, b| mean(*a).total_cmp(&mean(*b)))
+            .expect("bots are only asked when they can act");
+        actions[best].clone()
+    }
+}
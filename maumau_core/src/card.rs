@@ -0,0 +1,346 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serialized as its name, e.g. `"Hearts"`. Parsing ignores case and also
+/// accepts the one letter code, e.g. `"h"`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+}
+
+impl Suit {
+    /// One letter code used in compact card codes like `"H7"`.
+    pub fn code(&self) -> char {
+        match self {
+            Suit::Clubs => 'C',
+            Suit::Diamonds => 'D',
+            Suit::Hearts => 'H',
+            Suit::Spades => 'S',
+        }
+    }
+}
+
+impl FromStr for Suit {
+    type Err = CardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "clubs" | "c" => Ok(Suit::Clubs),
+            "diamonds" | "d" => Ok(Suit::Diamonds),
+            "hearts" | "h" => Ok(Suit::Hearts),
+            "spades" | "s" => Ok(Suit::Spades),
+            _ => Err(CardError::InvalidSuit(s.to_owned())),
+        }
+    }
+}
+
+impl Display for Suit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let suit = match self {
+            Suit::Clubs => "Clubs",
+            Suit::Diamonds => "Diamonds",
+            Suit::Hearts => "Hearts",
+            Suit::Spades => "Spades",
+        };
+        write!(f, "{}", suit)
+    }
+}
+
+/// Serialized as it is displayed, e.g. `"7"` or `"Jack"`. Parsing ignores
+/// case and also accepts the one letter codes of the face cards, e.g. `"j"`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Rank {
+    Ace,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+}
+
+impl Rank {
+    /// All ranks in ascending order, which is also their order within a suit
+    /// when card ids are assigned.
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+}
+
+impl Rank {
+    /// Code used in compact card codes like `"SJ"`: the number, or the first
+    /// letter for face cards and aces.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Rank::Ace => "A",
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+        }
+    }
+}
+
+impl FromStr for Rank {
+    type Err = CardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        Rank::ALL
+            .into_iter()
+            .find(|rank| {
+                rank.to_string().to_ascii_lowercase() == name
+                    || rank.code().to_ascii_lowercase() == name
+            })
+            .ok_or_else(|| CardError::InvalidRank(s.to_owned()))
+    }
+}
+
+impl Display for Rank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rank = match self {
+            Rank::Ace => "Ace",
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "Jack",
+            Rank::Queen => "Queen",
+            Rank::King => "King",
+        };
+        write!(f, "{}", rank)
+    }
+}
+
+/// A physical card. Cards of the same suit and rank from different copies of
+/// a deck are told apart by their id, which encodes copy, suit and rank as
+/// `copy * 52 + suit * 13 + rank`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Card {
+    pub id: u8,
+    pub suit: Suit,
+    pub rank: Rank,
+}
+
+impl Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} of {}", self.rank, self.suit)
+    }
+}
+
+impl Card {
+    pub fn new(suit: Suit, rank: Rank) -> Self {
+        Self::from_copy(0, suit, rank)
+    }
+
+    pub fn from_copy(copy: u8, suit: Suit, rank: Rank) -> Self {
+        let suit_index = Suit::ALL.iter().position(|s| *s == suit).unwrap() as u8;
+        let rank_index = Rank::ALL.iter().position(|r| *r == rank).unwrap() as u8;
+        Self {
+            id: copy * CARDS_PER_COPY + suit_index * 13 + rank_index,
+            suit,
+            rank,
+        }
+    }
+
+    /// Compact code of suit and rank, e.g. `"H7"` or `"SJ"`. Copies of the
+    /// same card share their code.
+    pub fn code(&self) -> String {
+        format!("{}{}", self.suit.code(), self.rank.code())
+    }
+
+    pub fn to_dto(&self) -> CardDTO {
+        CardDTO::new(self.clone())
+    }
+
+    pub fn is_playable_on(&self, other: &Card) -> bool {
+        self.suit == other.suit || self.rank == other.rank
+    }
+}
+
+/// Parses a compact code like `"H7"`, `"s10"` or `"DQ"` into the card of
+/// the first copy.
+impl FromStr for Card {
+    type Err = CardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim();
+        let invalid = || CardError::InvalidCode(s.to_owned());
+        let suit_end = code.chars().next().ok_or_else(invalid)?.len_utf8();
+        let suit = code[..suit_end].parse().map_err(|_| invalid())?;
+        let rank = code[suit_end..].parse().map_err(|_| invalid())?;
+        Ok(Card::new(suit, rank))
+    }
+}
+
+/// A card as it is sent to and received from clients. Besides the full
+/// form it can be given as a compact code like `"H7"`, which stands for the
+/// card of the first copy.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(try_from = "CardDTORepr")]
+pub struct CardDTO {
+    pub id: u8,
+    pub suit: Suit,
+    pub rank: Rank,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CardDTORepr {
+    Code(String),
+    Full { id: u8, suit: Suit, rank: Rank },
+}
+
+impl TryFrom<CardDTORepr> for CardDTO {
+    type Error = CardError;
+
+    fn try_from(repr: CardDTORepr) -> Result<Self, Self::Error> {
+        match repr {
+            CardDTORepr::Code(code) => code.parse().map(CardDTO::new),
+            CardDTORepr::Full { id, suit, rank } => Ok(Self { id, suit, rank }),
+        }
+    }
+}
+
+impl CardDTO {
+    pub fn new(card: Card) -> Self {
+        Self {
+            id: card.id,
+            suit: card.suit,
+            rank: card.rank,
+        }
+    }
+
+    pub fn to_card(&self) -> Result<Card, CardError> {
+        let card = Card::try_from(self.id)?;
+        if card.suit != self.suit || card.rank != self.rank {
+            return Err(CardError::WrongId {
+                card_id: self.id,
+                card: Card::from_copy(
+                    self.id / CARDS_PER_COPY,
+                    self.suit.clone(),
+                    self.rank.clone(),
+                ),
+            });
+        }
+        Ok(card)
+    }
+
+    /// See [`Card::code`].
+    pub fn code(&self) -> String {
+        format!("{}{}", self.suit.code(), self.rank.code())
+    }
+}
+
+impl TryFrom<u8> for Card {
+    type Error = CardError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        if value >= MAX_COPIES * CARDS_PER_COPY {
+            return Err(CardError::InvalidIndex(value));
+        }
+        let index = value % CARDS_PER_COPY;
+        Ok(Self {
+            id: value,
+            suit: Suit::ALL[(index / 13) as usize].clone(),
+            rank: Rank::ALL[(index % 13) as usize].clone(),
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CardError {
+    InvalidSuit(String),
+    InvalidRank(String),
+    /// Not a compact card code like `"H7"`.
+    InvalidCode(String),
+    /// The id is beyond the last copy of a deck.
+    InvalidIndex(u8),
+    /// Suit and rank were given along with an id that belongs to another
+    /// card.
+    WrongId {
+        card_id: u8,
+        card: Card,
+    },
+}
+
+impl Display for CardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardError::InvalidSuit(suit) => write!(f, "\"{}\" is not a suit", suit),
+            CardError::InvalidRank(rank) => write!(f, "\"{}\" is not a rank", rank),
+            CardError::InvalidCode(code) => write!(f, "\"{}\" is not a card code", code),
+            CardError::InvalidIndex(card_id) => write!(f, "{} is not a card id", card_id),
+            CardError::WrongId { card_id, card } => {
+                write!(f, "card id {} does not belong to the {}", card_id, card)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CardError {}
+
+macro_rules! serde_via_str {
+    ($type:ty) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+serde_via_str!(Suit);
+serde_via_str!(Rank);
+
+/// Number of distinct card ids per copy of a deck, one per French card.
+pub const CARDS_PER_COPY: u8 = 52;
+/// Highest number of combined deck copies that still fits into `u8` ids.
+pub const MAX_COPIES: u8 = 4;
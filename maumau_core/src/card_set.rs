@@ -0,0 +1,239 @@
+use std::ops::{BitAnd, BitOr, Sub};
+
+use crate::card::{Card, Rank, Suit, CARDS_PER_COPY, MAX_COPIES};
+
+const WORDS: usize = 4;
+
+/// An unordered set of cards with one bit per card id, so that hands and the
+/// deck can be filtered by suit or rank with a few bitwise operations.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct CardSet([u64; WORDS]);
+
+impl CardSet {
+    pub const fn new() -> Self {
+        Self([0; WORDS])
+    }
+
+    /// All cards of `suit`, in every copy of the deck.
+    pub fn of_suit(suit: &Suit) -> Self {
+        let suit_index = Suit::ALL.iter().position(|s| s == suit).unwrap() as u8;
+        (0..MAX_COPIES)
+            .flat_map(|copy| {
+                let first = copy * CARDS_PER_COPY + suit_index * 13;
+                first..first + 13
+            })
+            .collect()
+    }
+
+    /// All cards of `rank`, in every copy of the deck.
+    pub fn of_rank(rank: &Rank) -> Self {
+        let rank_index = Rank::ALL.iter().position(|r| r == rank).unwrap() as u8;
+        (0..MAX_COPIES)
+            .flat_map(|copy| (0..4).map(move |suit| copy * CARDS_PER_COPY + suit * 13 + rank_index))
+            .collect()
+    }
+
+    /// Adds a card id, returning whether it was not in the set yet.
+    pub fn insert(&mut self, card_id: u8) -> bool {
+        let (word, bit) = Self::position(card_id);
+        let inserted = self.0[word] & bit == 0;
+        self.0[word] |= bit;
+        inserted
+    }
+
+    /// Removes a card id, returning whether it was in the set.
+    pub fn remove(&mut self, card_id: u8) -> bool {
+        let (word, bit) = Self::position(card_id);
+        let removed = self.0[word] & bit != 0;
+        self.0[word] &= !bit;
+        removed
+    }
+
+    pub fn contains(&self, card_id: u8) -> bool {
+        let (word, bit) = Self::position(card_id);
+        self.0[word] & bit != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|word| *word == 0)
+    }
+
+    /// The `n`th smallest card id in the set.
+    pub fn nth(&self, mut n: usize) -> Option<u8> {
+        for (index, word) in self.0.iter().enumerate() {
+            let ones = word.count_ones() as usize;
+            if n >= ones {
+                n -= ones;
+                continue;
+            }
+            let mut word = *word;
+            for _ in 0..n {
+                word &= word - 1;
+            }
+            return Some((index * 64) as u8 + word.trailing_zeros() as u8);
+        }
+        None
+    }
+
+    /// Card ids in ascending order.
+    pub fn ids(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.iter().enumerate().flat_map(|(index, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros();
+                word &= word - 1;
+                Some((index * 64) as u8 + bit as u8)
+            })
+        })
+    }
+
+    /// Cards in ascending order of their ids.
+    pub fn iter(&self) -> impl Iterator<Item = Card> + '_ {
+        self.ids()
+            .map(|card_id| Card::try_from(card_id).expect("card sets only hold valid ids"))
+    }
+
+    fn position(card_id: u8) -> (usize, u64) {
+        (card_id as usize / 64, 1 << (card_id % 64))
+    }
+}
+
+impl FromIterator<u8> for CardSet {
+    fn from_iter<T: IntoIterator<Item = u8>>(iter: T) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<T: IntoIterator<Item = Card>>(iter: T) -> Self {
+        iter.into_iter().map(|card| card.id).collect()
+    }
+}
+
+impl Extend<u8> for CardSet {
+    fn extend<T: IntoIterator<Item = u8>>(&mut self, iter: T) {
+        for card_id in iter {
+            self.insert(card_id);
+        }
+    }
+}
+
+impl Extend<Card> for CardSet {
+    fn extend<T: IntoIterator<Item = Card>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().map(|card| card.id));
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(std::array::from_fn(|word| self.0[word] | other.0[word]))
+    }
+}
+
+impl BitAnd for CardSet {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        Self(std::array::from_fn(|word| self.0[word] & other.0[word]))
+    }
+}
+
+impl Sub for CardSet {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(std::array::from_fn(|word| self.0[word] & !other.0[word]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_remove_and_len() {
+        let mut set = CardSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert(3));
+        assert!(set.insert(200));
+        assert!(!set.insert(3));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(3) && set.contains(200) && !set.contains(4));
+        assert!(set.remove(3));
+        assert!(!set.remove(3));
+        assert_eq!(set.len(), 1);
+        assert!(set.remove(200));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn ids_are_ascending_across_words() {
+        let set: CardSet = [130, 0, 63, 64, 207].into_iter().collect();
+        assert_eq!(set.ids().collect::<Vec<_>>(), vec![0, 63, 64, 130, 207]);
+    }
+
+    #[test]
+    fn nth_matches_ids() {
+        let set: CardSet = [5, 17, 63, 64, 65, 127, 128, 191, 192, 207]
+            .into_iter()
+            .collect();
+        let ids: Vec<u8> = set.ids().collect();
+        for (n, card_id) in ids.iter().enumerate() {
+            assert_eq!(set.nth(n), Some(*card_id));
+        }
+        assert_eq!(set.nth(ids.len()), None);
+        assert_eq!(CardSet::new().nth(0), None);
+    }
+
+    #[test]
+    fn suits_and_ranks_cover_every_copy() {
+        let hearts = CardSet::of_suit(&Suit::Hearts);
+        let sevens = CardSet::of_rank(&Rank::Seven);
+        assert_eq!(hearts.len(), 13 * MAX_COPIES as usize);
+        assert_eq!(sevens.len(), 4 * MAX_COPIES as usize);
+        let seven_of_hearts: Vec<u8> = (hearts & sevens).ids().collect();
+        let expected: Vec<u8> = (0..MAX_COPIES)
+            .map(|copy| Card::from_copy(copy, Suit::Hearts, Rank::Seven).id)
+            .collect();
+        assert_eq!(seven_of_hearts, expected);
+        assert!(hearts
+            .iter()
+            .chain(sevens.iter())
+            .all(|card| card.suit == Suit::Hearts || card.rank == Rank::Seven));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: CardSet = [1, 2, 3, 100].into_iter().collect();
+        let b: CardSet = [3, 4, 100, 150].into_iter().collect();
+        assert_eq!(
+            (a | b).ids().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 100, 150]
+        );
+        assert_eq!((a & b).ids().collect::<Vec<_>>(), vec![3, 100]);
+        assert_eq!((a - b).ids().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(a - a, CardSet::new());
+    }
+
+    #[test]
+    fn collects_cards_by_id() {
+        let cards = vec![
+            Card::new(Suit::Spades, Rank::Jack),
+            Card::from_copy(1, Suit::Spades, Rank::Jack),
+        ];
+        let set: CardSet = cards.clone().into_iter().collect();
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.iter().collect::<Vec<_>>(), cards);
+    }
+}
@@ -0,0 +1,119 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    card::{Card, Rank, Suit, MAX_COPIES},
+    card_set::CardSet,
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum DeckKind {
+    /// 32 cards, 7 to Ace in every suit.
+    Skat,
+    /// 52 cards, 2 to Ace in every suit.
+    French,
+}
+
+/// Which cards a game is played with: `copies` combined decks of `kind`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeckSpec {
+    pub kind: DeckKind,
+    pub copies: u8,
+}
+
+impl Default for DeckSpec {
+    fn default() -> Self {
+        Self {
+            kind: DeckKind::Skat,
+            copies: 1,
+        }
+    }
+}
+
+impl DeckSpec {
+    pub fn is_valid(&self) -> bool {
+        (1..=MAX_COPIES).contains(&self.copies)
+    }
+
+    pub fn ranks(&self) -> &'static [Rank] {
+        match self.kind {
+            DeckKind::Skat => &Rank::ALL[5..],
+            DeckKind::French => &Rank::ALL,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranks().len() * Suit::ALL.len() * self.copies as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All cards of the given copy of this deck.
+    pub fn copy_cards(&self, copy: u8) -> Vec<Card> {
+        Suit::ALL
+            .iter()
+            .flat_map(|suit| {
+                self.ranks()
+                    .iter()
+                    .map(move |rank| Card::from_copy(copy, suit.clone(), rank.clone()))
+            })
+            .collect()
+    }
+
+    pub fn cards(&self) -> Vec<Card> {
+        (0..self.copies)
+            .flat_map(|copy| self.copy_cards(copy))
+            .collect()
+    }
+}
+
+/// The undrawn cards. Their order does not matter, every draw picks a
+/// random card.
+pub struct Deck {
+    pub cards: CardSet,
+}
+
+impl Deck {
+    pub fn new(spec: &DeckSpec) -> Self {
+        Self {
+            cards: spec.cards().into_iter().collect(),
+        }
+    }
+
+    pub fn draw<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<Card> {
+        if self.cards.is_empty() {
+            return None;
+        }
+        let card_id = self.cards.nth(rng.gen_range(0..self.cards.len()))?;
+        self.cards.remove(card_id);
+        Card::try_from(card_id).ok()
+    }
+
+    pub fn draw_many<R: Rng + ?Sized>(&mut self, n: usize, rng: &mut R) -> Option<Vec<Card>> {
+        if n > self.len() {
+            return None;
+        }
+        (0..n).map(|_| self.draw(rng)).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    pub fn add_cards(&mut self, cards: Vec<Card>) {
+        self.cards.extend(cards);
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Self::new(&DeckSpec::default())
+    }
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    card::Suit,
+    game::{Announcement, Direction},
+};
+
+/// Something that happened in a game, in the order it happened. `player_id`
+/// is the player whose action caused the event, -1 for the game itself.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameEvent {
+    pub seq: u64,
+    pub player_id: i64,
+    pub kind: GameEventKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum GameEventKind {
+    CardPlayed {
+        card_id: u8,
+    },
+    CardsDrawn {
+        card_ids: Vec<u8>,
+    },
+    /// Cards drawn by another player, as shown in a
+    /// [`PlayerView`](crate::view::PlayerView). Never emitted by the game.
+    CardsDrawnFaceDown {
+        num_cards: usize,
+    },
+    SuitWished {
+        suit: Suit,
+    },
+    PlayerSkipped {
+        skipped_player_id: i64,
+    },
+    DirectionChanged {
+        direction: Direction,
+    },
+    TurnEnded,
+    DeckReshuffled {
+        num_cards: usize,
+    },
+    DeckAdded {
+        num_cards: usize,
+    },
+    RoundDrawn,
+    Announced {
+        announcement: Announcement,
+    },
+    MauChallenged {
+        challenged_player_id: i64,
+    },
+    PlayerWon,
+    PlayerFinished {
+        place: usize,
+    },
+}
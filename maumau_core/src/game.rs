@@ -0,0 +1,1625 @@
+use std::fmt::Display;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    card::{Card, CardError, Suit, CARDS_PER_COPY, MAX_COPIES},
+    card_set::CardSet,
+    deck::Deck,
+    event::{GameEvent, GameEventKind},
+    player::{LobbyPlayer, Player, PlayerError},
+    rules::{DeckExhaustion, RuleSet, StartingPlayer},
+    view::{self, PlayerView},
+};
+
+pub struct Game {
+    pub id: i64,
+    pub lobby_id: i64,
+    deck: Deck,
+    pub discard_pile: Vec<Card>,
+    pub current_turn_player: i64,
+    pub winner: Option<i64>,
+    /// Players in the order they got rid of their cards.
+    pub placements: Vec<i64>,
+    pub players: Vec<Player>,
+    pub actions: Vec<PlayerAction>,
+    pub events: Vec<GameEvent>,
+    pub rules: RuleSet,
+    pub direction: Direction,
+    pub turn_state: TurnState,
+    pub wished_suit: Option<Suit>,
+    /// Players skipped by the most recently played card.
+    pub skipped_players: Vec<i64>,
+    pub announcements: Vec<MauCall>,
+    /// Player who went down to one card without calling "Mau". Opponents can
+    /// challenge them until someone else acts.
+    pub missed_mau: Option<i64>,
+    /// Seed of `rng`. Together with `actions` it reproduces the whole game.
+    pub seed: u64,
+    rng: StdRng,
+    /// Seat index of the dealer, the player after them starts.
+    pub dealer: usize,
+    /// Copies of the deck in the game, including fresh ones added when the
+    /// deck ran out.
+    pub decks_in_play: u8,
+}
+
+/// What the player whose turn it is has to do next.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum TurnState {
+    AwaitingPlay,
+    MustDrawPenalty {
+        n: u8,
+    },
+    AwaitingSuitChoice,
+    MayPlayDrawnCard {
+        card_id: u8,
+    },
+    /// [`TurnState::MayPlayDrawnCard`] as the other seats see it, without
+    /// the card that was drawn.
+    MayPlayHiddenDrawnCard,
+    GameOver,
+}
+
+impl TurnState {
+    /// This state as seats other than the current player's see it.
+    pub fn hide_drawn_card(&self) -> TurnState {
+        match self {
+            TurnState::MayPlayDrawnCard { .. } => TurnState::MayPlayHiddenDrawnCard,
+            state => state.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+pub enum Direction {
+    Clockwise,
+    CounterClockwise,
+}
+
+impl Display for TurnState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TurnState::AwaitingPlay => write!(f, "a card has to be played"),
+            TurnState::MustDrawPenalty { n } => write!(f, "{} cards have to be drawn", n),
+            TurnState::AwaitingSuitChoice => write!(f, "a suit has to be wished"),
+            TurnState::MayPlayDrawnCard { .. } | TurnState::MayPlayHiddenDrawnCard => {
+                write!(f, "only the drawn card can be played")
+            }
+            TurnState::GameOver => write!(f, "the game is over"),
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let direction = match self {
+            Direction::Clockwise => "clockwise",
+            Direction::CounterClockwise => "counter-clockwise",
+        };
+        write!(f, "{}", direction)
+    }
+}
+
+impl Direction {
+    pub fn reversed(self) -> Self {
+        match self {
+            Direction::Clockwise => Direction::CounterClockwise,
+            Direction::CounterClockwise => Direction::Clockwise,
+        }
+    }
+}
+
+/// Effects of earlier cards that currently restrict or alter play.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveConstraints {
+    pub wished_suit: Option<Suit>,
+    pub pending_draw: Option<u8>,
+    pub skipped_players: Vec<i64>,
+    pub direction: Direction,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+pub enum Announcement {
+    Mau,
+    MauMau,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MauCall {
+    pub player_id: i64,
+    pub announcement: Announcement,
+}
+
+#[derive(PartialEq, Clone)]
+pub struct PlayerAction {
+    pub action: Action,
+    pub player_id: i64,
+}
+
+impl Game {
+    pub fn new(
+        players: Vec<LobbyPlayer>,
+        lobby_id: i64,
+        id: i64,
+        rules: RuleSet,
+        seed: Option<u64>,
+        dealer: usize,
+    ) -> Self {
+        assert!(players.len() > 1);
+        let seed = seed.unwrap_or_else(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let starting_seat = match rules.starting_player {
+            StartingPlayer::Random => rng.gen_range(0..players.len()),
+            StartingPlayer::Dealer => dealer,
+            StartingPlayer::LeftOfDealer => dealer + 1,
+        } % players.len();
+        let starting_player = &players[starting_seat];
+        let players = players
+            .iter()
+            .map(|player| Player::new(player.clone()))
+            .collect();
+
+        Self {
+            current_turn_player: starting_player.user_id,
+            decks_in_play: rules.deck.copies,
+            lobby_id,
+            id,
+            deck: Deck::new(&rules.deck),
+            discard_pile: vec![],
+            winner: None,
+            placements: vec![],
+            players,
+            actions: vec![],
+            events: vec![],
+            rules,
+            direction: Direction::Clockwise,
+            turn_state: TurnState::AwaitingPlay,
+            wished_suit: None,
+            skipped_players: vec![],
+            announcements: vec![],
+            missed_mau: None,
+            seed,
+            rng,
+            dealer,
+        }
+    }
+
+    /// A game that looks exactly like `view` to its seat, with the cards
+    /// hidden from it dealt to the other hands and the deck at random. Used
+    /// by bots to play out what might happen next.
+    pub fn determinize<R: Rng + ?Sized>(view: &PlayerView, rng: &mut R) -> Self {
+        let mut unseen: CardSet = (0..view.decks_in_play)
+            .flat_map(|copy| view.rules.deck.copy_cards(copy))
+            .collect();
+        unseen = unseen - view.hand - view.discard_pile.iter().cloned().collect();
+        let mut hidden = Deck { cards: unseen };
+        let mut players: Vec<Player> = view
+            .opponents
+            .iter()
+            .map(|opponent| {
+                let mut player = Player::new(LobbyPlayer {
+                    user_id: opponent.user_id,
+                    username: opponent.username.clone(),
+                    bot: None,
+                });
+                player.hand.extend(
+                    hidden
+                        .draw_many(opponent.hand_size, rng)
+                        .expect("opponents hold unseen cards"),
+                );
+                player
+            })
+            .collect();
+        let mut own_seat = Player::new(LobbyPlayer {
+            user_id: view.player_id,
+            username: String::new(),
+            bot: None,
+        });
+        own_seat.hand = view.hand;
+        players.insert(view.seat, own_seat);
+        let turn_state = match &view.turn_state {
+            // the drawn card is one of those just dealt to the current player
+            TurnState::MayPlayHiddenDrawnCard => {
+                let hand = players
+                    .iter()
+                    .find(|player| player.lobby_player.user_id == view.current_player)
+                    .map(|player| player.hand)
+                    .unwrap_or_default();
+                match hand.nth(rng.gen_range(0..hand.len().max(1))) {
+                    Some(card_id) => TurnState::MayPlayDrawnCard { card_id },
+                    None => TurnState::AwaitingPlay,
+                }
+            }
+            state => state.clone(),
+        };
+        let seed = rng.gen();
+
+        Self {
+            id: view.game_id,
+            lobby_id: view.lobby_id,
+            deck: hidden,
+            discard_pile: view.discard_pile.clone(),
+            current_turn_player: view.current_player,
+            winner: view.winner,
+            placements: view.placements.clone(),
+            players,
+            actions: vec![],
+            events: view.events.clone(),
+            rules: view.rules.clone(),
+            direction: view.constraints.direction,
+            turn_state,
+            wished_suit: view.constraints.wished_suit.clone(),
+            skipped_players: view.constraints.skipped_players.clone(),
+            announcements: view.announcements.clone(),
+            missed_mau: view.missed_mau,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            dealer: 0,
+            decks_in_play: view.decks_in_play,
+        }
+    }
+
+    pub fn deck_size(&self) -> usize {
+        self.deck.len()
+    }
+
+    /// Rebuilds a game from its seed and the first entries of its action log.
+    /// Entries recorded by the game itself (player -1) are reproduced by the
+    /// seeded setup and skipped.
+    pub fn replay(
+        seed: u64,
+        players: Vec<LobbyPlayer>,
+        rules: RuleSet,
+        dealer: usize,
+        actions: &[PlayerAction],
+    ) -> Result<Self, DoActionError> {
+        let mut game = Game::new(players, 0, 0, rules, Some(seed), dealer);
+        game.start();
+        for action in actions.iter().filter(|action| action.player_id != -1) {
+            game.apply(action.player_id, action.action.clone())?;
+        }
+        Ok(game)
+    }
+
+    /// The state of this game after the first `step` entries of its action log.
+    pub fn state_at(&self, step: usize) -> Result<Self, DoActionError> {
+        let players = self
+            .players
+            .iter()
+            .map(|player| player.lobby_player.clone())
+            .collect();
+        let step = step.min(self.actions.len());
+        let mut game = Game::replay(
+            self.seed,
+            players,
+            self.rules.clone(),
+            self.dealer,
+            &self.actions[..step],
+        )?;
+        game.id = self.id;
+        game.lobby_id = self.lobby_id;
+        Ok(game)
+    }
+
+    pub fn start(&mut self) {
+        self.give_cards();
+        self.turn_top_card();
+    }
+
+    pub fn give_cards(&mut self) {
+        for player in &mut self.players {
+            let new_hand = self
+                .deck
+                .draw_many(self.rules.hand_size as usize, &mut self.rng)
+                .unwrap();
+            player.hand.extend(new_hand);
+        }
+    }
+
+    pub fn turn_top_card(&mut self) {
+        let card = self.deck.draw(&mut self.rng).unwrap();
+        self.actions.push(PlayerAction {
+            action: Action::PlayCard(card.id),
+            player_id: -1,
+        });
+        self.emit(-1, GameEventKind::CardPlayed { card_id: card.id });
+        if self.rules.opening_effects {
+            self.apply_opening_effect(&card);
+        }
+        self.discard_pile.push(card);
+    }
+
+    /// A turned up 7 makes the first player draw, an 8 skips them and a jack
+    /// lets them wish the suit they then have to follow.
+    fn apply_opening_effect(&mut self, card: &Card) {
+        if self.rules.is_penalty_card(card) {
+            let n = self.rules.seven_penalty.unwrap_or_default();
+            self.turn_state = TurnState::MustDrawPenalty { n };
+        } else if self.rules.is_skip_card(card) {
+            for skip in 0..self.rules.eight_skips.unwrap_or_default() {
+                if skip > 0 {
+                    self.next_player();
+                }
+                let skipped_player_id = self.current_turn_player;
+                self.skipped_players.push(skipped_player_id);
+                self.emit(-1, GameEventKind::PlayerSkipped { skipped_player_id });
+            }
+            self.next_player();
+        } else if self.rules.is_wish_card(card) {
+            self.turn_state = TurnState::AwaitingSuitChoice;
+        }
+    }
+
+    /// Whether the card on top of the discard pile was turned up at the start
+    /// rather than played by someone.
+    fn opening_card_on_top(&self) -> bool {
+        let top_card_player = self.events.iter().rev().find_map(|event| match event.kind {
+            GameEventKind::CardPlayed { .. } => Some(event.player_id),
+            _ => None,
+        });
+        top_card_player == Some(-1)
+    }
+
+    /// Whether `card` fits on the discard pile, and why not if it does not.
+    pub fn check_playable(&self, card: &Card) -> Result<(), PlayCardError> {
+        if let Some(wished_suit) = &self.wished_suit {
+            if &card.suit != wished_suit {
+                return Err(PlayCardError::NotWishedSuit {
+                    card: card.clone(),
+                    wished_suit: wished_suit.clone(),
+                });
+            }
+            return Ok(());
+        }
+        let top_card = self.discard_pile.last().unwrap();
+        if !card.is_playable_on(top_card) {
+            return Err(PlayCardError::DoesNotMatch {
+                card: card.clone(),
+                top_card: top_card.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn viable_actions(&self, player_id: i64) -> Vec<Action> {
+        let player = match self
+            .players
+            .iter()
+            .find(|player| player.lobby_player.user_id == player_id)
+        {
+            Some(player) => player,
+            None => return vec![],
+        };
+        let mut actions = vec![];
+        if let Some(missed) = self.missed_mau {
+            if missed != player_id && self.turn_state != TurnState::GameOver {
+                actions.push(Action::ChallengeMau(missed));
+            }
+        }
+        if self.current_turn_player != player_id {
+            return actions;
+        }
+        let turn_actions = self.turn_actions(player);
+        if self.rules.mau_penalty.is_some() && self.turn_state != TurnState::AwaitingSuitChoice {
+            // announce for the hand left after the play of this turn: any
+            // number of same-rank cards up to the largest play can be played
+            let most_played = turn_actions
+                .iter()
+                .map(|action| action.played_cards().len())
+                .max()
+                .unwrap_or_default()
+                .max(1);
+            let hand_sizes_after_play =
+                player.hand.len().saturating_sub(most_played)..player.hand.len();
+            for (announcement, hand_size) in [(Announcement::Mau, 1), (Announcement::MauMau, 0)] {
+                if hand_sizes_after_play.contains(&hand_size)
+                    && !self.has_announced(player_id, announcement)
+                {
+                    actions.push(match announcement {
+                        Announcement::Mau => Action::CallMau,
+                        Announcement::MauMau => Action::CallMauMau,
+                    });
+                }
+            }
+        }
+        actions.extend(turn_actions);
+        actions
+    }
+
+    fn has_announced(&self, player_id: i64, announcement: Announcement) -> bool {
+        self.announcements
+            .iter()
+            .any(|call| call.player_id == player_id && call.announcement == announcement)
+    }
+
+    fn turn_actions(&self, player: &Player) -> Vec<Action> {
+        match self.turn_state {
+            TurnState::AwaitingPlay => {
+                let playable_cards: Vec<Action> = (player.hand & self.playable_cards())
+                    .ids()
+                    .map(Action::PlayCard)
+                    .collect();
+                if playable_cards.is_empty() {
+                    vec![Action::DrawCards(1)]
+                } else {
+                    let multi_plays = self.multi_plays(player, &playable_cards);
+                    [playable_cards, multi_plays].concat()
+                }
+            }
+            TurnState::MustDrawPenalty { n } => {
+                let mut actions: Vec<Action> = if self.rules.seven_stacking {
+                    (player.hand & self.rules.penalty_cards())
+                        .ids()
+                        .map(Action::PlayCard)
+                        .collect()
+                } else {
+                    vec![]
+                };
+                actions.extend(self.multi_plays(player, &actions));
+                actions.push(Action::DrawCards(n));
+                actions
+            }
+            TurnState::AwaitingSuitChoice => vec![
+                Action::DecideSuit(Suit::Hearts),
+                Action::DecideSuit(Suit::Diamonds),
+                Action::DecideSuit(Suit::Clubs),
+                Action::DecideSuit(Suit::Spades),
+            ],
+            TurnState::MayPlayDrawnCard { card_id } => {
+                let mut actions = vec![];
+                if (player.hand & self.playable_cards()).contains(card_id) {
+                    actions.push(Action::PlayCard(card_id));
+                }
+                actions.push(Action::CannotPlay);
+                actions
+            }
+            TurnState::MayPlayHiddenDrawnCard | TurnState::GameOver => vec![],
+        }
+    }
+
+    /// Plays of several cards that start with one of the single `plays`,
+    /// one for every card of the same rank that could end up on top.
+    fn multi_plays(&self, player: &Player, plays: &[Action]) -> Vec<Action> {
+        if !self.rules.multi_card_plays {
+            return vec![];
+        }
+        let mut actions = vec![];
+        for lead in plays.iter().flat_map(|action| action.played_cards()) {
+            let Ok(lead_card) = Card::try_from(*lead) else {
+                continue;
+            };
+            let mut same_rank = player.hand & CardSet::of_rank(&lead_card.rank);
+            same_rank.remove(*lead);
+            let same_rank: Vec<u8> = same_rank.ids().collect();
+            for top in &same_rank {
+                let mut card_ids = vec![*lead];
+                card_ids.extend(same_rank.iter().filter(|id| *id != top));
+                card_ids.push(*top);
+                actions.push(Action::PlayCards(card_ids));
+            }
+        }
+        actions
+    }
+
+    /// Whether `player_id` may take `action` right now. Plays of several
+    /// cards are checked against the rules rather than the generated list, so
+    /// any subset and order of same-rank cards is accepted.
+    fn is_viable(&self, player_id: i64, action: &Action) -> bool {
+        let Action::PlayCards(card_ids) = action else {
+            return self.viable_actions(player_id).contains(action);
+        };
+        let Some((lead, rest)) = card_ids.split_first() else {
+            return false;
+        };
+        if !self.rules.multi_card_plays
+            || rest.is_empty()
+            || matches!(self.turn_state, TurnState::MayPlayDrawnCard { .. })
+            || !self.is_viable(player_id, &Action::PlayCard(*lead))
+        {
+            return false;
+        }
+        let Some(player) = self
+            .players
+            .iter()
+            .find(|player| player.lobby_player.user_id == player_id)
+        else {
+            return false;
+        };
+        let Ok(lead_card) = Card::try_from(*lead) else {
+            return false;
+        };
+        let same_rank = player.hand & CardSet::of_rank(&lead_card.rank);
+        rest.iter().enumerate().all(|(index, card_id)| {
+            !card_ids[..=index].contains(card_id) && same_rank.contains(*card_id)
+        })
+    }
+
+    /// Cards that could go on the discard pile right now, following the
+    /// wished suit if there is one.
+    fn playable_cards(&self) -> CardSet {
+        if let Some(suit) = &self.wished_suit {
+            return CardSet::of_suit(suit);
+        }
+        let top_card = self.discard_pile.last().unwrap();
+        CardSet::of_suit(&top_card.suit) | CardSet::of_rank(&top_card.rank)
+    }
+
+    fn play_card(&mut self, card: Card) -> Result<(), PlayCardError> {
+        self.check_playable(&card)?;
+        self.discard_pile.push(card);
+        Ok(())
+    }
+
+    fn player_mut(&mut self, player_id: i64) -> Option<&mut Player> {
+        self.players
+            .iter_mut()
+            .find(|player| player.lobby_player.user_id == player_id)
+    }
+
+    /// The turn state as `player_id` may know it: only the current player
+    /// learns which card they drew.
+    fn turn_state_for(&self, player_id: i64) -> TurnState {
+        if player_id == self.current_turn_player {
+            self.turn_state.clone()
+        } else {
+            self.turn_state.hide_drawn_card()
+        }
+    }
+
+    pub fn active_constraints(&self) -> ActiveConstraints {
+        let pending_draw = match self.turn_state {
+            TurnState::MustDrawPenalty { n } => Some(n),
+            _ => None,
+        };
+        ActiveConstraints {
+            wished_suit: self.wished_suit.clone(),
+            pending_draw,
+            skipped_players: self.skipped_players.clone(),
+            direction: self.direction,
+        }
+    }
+
+    /// The game as seen from the seat of `player_id`, `None` if they do not
+    /// play in it.
+    pub fn player_view(&self, player_id: i64) -> Option<PlayerView> {
+        let seat = self
+            .players
+            .iter()
+            .position(|player| player.lobby_player.user_id == player_id)?;
+        Some(PlayerView {
+            game_id: self.id,
+            lobby_id: self.lobby_id,
+            player_id,
+            seat,
+            hand: self.players[seat].hand,
+            opponents: self
+                .players
+                .iter()
+                .filter(|player| player.lobby_player.user_id != player_id)
+                .map(|player| player.to_dto())
+                .collect(),
+            discard_pile: self.discard_pile.clone(),
+            deck_size: self.deck_size(),
+            decks_in_play: self.decks_in_play,
+            current_player: self.current_turn_player,
+            turn_state: self.turn_state_for(player_id),
+            constraints: self.active_constraints(),
+            viable_actions: self.viable_actions(player_id),
+            winner: self.winner,
+            placements: self.placements.clone(),
+            announcements: self.announcements.clone(),
+            missed_mau: self.missed_mau,
+            rules: self.rules.clone(),
+            events: self
+                .events
+                .iter()
+                .map(|event| view::redact(event, player_id))
+                .collect(),
+        })
+    }
+
+    /// Checks that every card of the decks in play is in exactly one place,
+    /// the deck, the discard pile or a hand, and that the turn belongs to a
+    /// seated player who is still in the game.
+    pub fn check_invariants(&self) -> Result<(), Vec<InvariantViolation>> {
+        let mut violations = vec![];
+        let mut counts = [0usize; MAX_COPIES as usize * CARDS_PER_COPY as usize];
+        let card_ids = self
+            .deck
+            .cards
+            .ids()
+            .chain(self.discard_pile.iter().map(|card| card.id))
+            .chain(self.players.iter().flat_map(|player| player.hand.ids()));
+        for card_id in card_ids {
+            match counts.get_mut(card_id as usize) {
+                Some(count) => *count += 1,
+                None => violations.push(InvariantViolation::UnexpectedCard { card_id }),
+            }
+        }
+        let expected: Vec<u8> = (0..self.decks_in_play)
+            .flat_map(|copy| self.rules.deck.copy_cards(copy))
+            .map(|card| card.id)
+            .collect();
+        for (card_id, count) in counts.iter().enumerate() {
+            let card_id = card_id as u8;
+            match (*count, expected.contains(&card_id)) {
+                (0, true) => violations.push(InvariantViolation::MissingCard { card_id }),
+                (1, true) | (0, false) => {}
+                (count, true) => {
+                    violations.push(InvariantViolation::DuplicateCard { card_id, count })
+                }
+                (_, false) => violations.push(InvariantViolation::UnexpectedCard { card_id }),
+            }
+        }
+
+        let seated = self
+            .players
+            .iter()
+            .any(|player| player.lobby_player.user_id == self.current_turn_player);
+        let game_over = self.turn_state == TurnState::GameOver;
+        if !seated || (!game_over && self.is_finished(self.current_turn_player)) {
+            violations.push(InvariantViolation::CurrentPlayerNotSeated {
+                player_id: self.current_turn_player,
+            });
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    pub fn is_finished(&self, player_id: i64) -> bool {
+        self.placements.contains(&player_id)
+    }
+
+    /// Passes the turn to the next player in the current direction, leaving
+    /// out players who already got rid of all their cards.
+    fn next_player(&mut self) {
+        let mut index = self
+            .players
+            .iter()
+            .position(|player| player.lobby_player.user_id == self.current_turn_player)
+            .unwrap();
+        let num_players = self.players.len();
+        for _ in 0..num_players {
+            index = match self.direction {
+                Direction::Clockwise => (index + 1) % num_players,
+                Direction::CounterClockwise => (index + num_players - 1) % num_players,
+            };
+            if !self.is_finished(self.players[index].lobby_player.user_id) {
+                break;
+            }
+        }
+        self.current_turn_player = self.players[index].lobby_player.user_id;
+    }
+
+    /// Records that `player_id` has no cards left. Returns whether the game is
+    /// over because of it.
+    fn finish_player(&mut self, player_id: i64) -> bool {
+        self.placements.push(player_id);
+        let place = self.placements.len();
+        if place == 1 {
+            self.winner = Some(player_id);
+            self.emit(player_id, GameEventKind::PlayerWon);
+        } else {
+            self.emit(player_id, GameEventKind::PlayerFinished { place });
+        }
+        let remaining: Vec<i64> = self
+            .players
+            .iter()
+            .map(|player| player.lobby_player.user_id)
+            .filter(|id| !self.is_finished(*id))
+            .collect();
+        if !self.rules.play_until_last {
+            return true;
+        }
+        if let [last] = remaining[..] {
+            self.placements.push(last);
+            self.emit(
+                last,
+                GameEventKind::PlayerFinished {
+                    place: self.placements.len(),
+                },
+            );
+            return true;
+        }
+        false
+    }
+
+    fn draw_many_cards(&mut self, player_id: i64, n: usize) -> Result<Vec<u8>, DrawCardError> {
+        if !self
+            .players
+            .iter()
+            .any(|player| player.lobby_player.user_id == player_id)
+        {
+            return Err(DrawCardError::PlayerNotFound(player_id));
+        }
+        if self.deck.len() < n {
+            self.reshuffle_discard_pile(player_id);
+        }
+        if self.deck.len() < n {
+            match self.rules.deck_exhaustion {
+                DeckExhaustion::EndRound => {
+                    // nobody wins, the transition keeps the game over
+                    self.emit(player_id, GameEventKind::RoundDrawn);
+                    self.turn_state = TurnState::GameOver;
+                    return Ok(vec![]);
+                }
+                DeckExhaustion::AddFreshDeck if self.decks_in_play < MAX_COPIES => {
+                    let fresh_cards = self.rules.deck.copy_cards(self.decks_in_play);
+                    self.decks_in_play += 1;
+                    let num_cards = fresh_cards.len();
+                    self.deck.add_cards(fresh_cards);
+                    self.emit(player_id, GameEventKind::DeckAdded { num_cards });
+                }
+                // whatever cannot be drawn is skipped
+                _ => {}
+            }
+        }
+        let n = n.min(self.deck.len());
+        let cards = self.deck.draw_many(n, &mut self.rng).unwrap();
+        let card_ids: Vec<u8> = cards.iter().map(|card| card.id).collect();
+        self.player_mut(player_id)
+            .ok_or(DrawCardError::PlayerNotFound(player_id))?
+            .hand
+            .extend(cards);
+        self.emit(
+            player_id,
+            GameEventKind::CardsDrawn {
+                card_ids: card_ids.clone(),
+            },
+        );
+        Ok(card_ids)
+    }
+
+    /// Shuffles all but the top card of the discard pile back into the deck.
+    fn reshuffle_discard_pile(&mut self, player_id: i64) {
+        let top_card = match self.discard_pile.pop() {
+            Some(card) => card,
+            None => return,
+        };
+        let reshuffled: Vec<Card> = self.discard_pile.drain(..).collect();
+        self.discard_pile.push(top_card);
+        if reshuffled.is_empty() {
+            return;
+        }
+        let num_cards = reshuffled.len();
+        self.deck.add_cards(reshuffled);
+        self.actions.push(PlayerAction {
+            action: Action::ReshuffleDeck,
+            player_id: -1,
+        });
+        self.emit(player_id, GameEventKind::DeckReshuffled { num_cards });
+    }
+
+    fn emit(&mut self, player_id: i64, kind: GameEventKind) {
+        self.events.push(GameEvent {
+            seq: self.events.len() as u64,
+            player_id,
+            kind,
+        });
+    }
+
+    /// Validates and executes `action` for `player_id`. This is the single
+    /// entry point for player moves: it checks the turn, whether the action is
+    /// currently viable, moves the card out of the hand, applies card effects
+    /// and detects the winner. The returned events show every drawn card; pass
+    /// them through [`view::redact`] before they reach a player.
+    pub fn apply(
+        &mut self,
+        player_id: i64,
+        action: Action,
+    ) -> Result<Vec<GameEvent>, DoActionError> {
+        if self.turn_state == TurnState::GameOver {
+            return Err(DoActionError::GameOver);
+        }
+        let is_challenge = matches!(action, Action::ChallengeMau(_));
+        if !is_challenge && self.current_turn_player != player_id {
+            return Err(DoActionError::NotYourTurn {
+                player_id,
+                current_player: self.current_turn_player,
+            });
+        }
+        if !self.is_viable(player_id, &action) {
+            return Err(self.explain_not_viable(player_id, action));
+        }
+        let first_event = self.events.len();
+        let mut drawn_cards = vec![];
+        match &action {
+            Action::PlayCard(card_id) => {
+                let card: Card = (*card_id).try_into()?;
+                self.check_playable(&card)?;
+                self.player_mut(player_id)
+                    .ok_or(DoActionError::PlayerNotFound(player_id))?
+                    .remove_card(&card)?;
+                self.play_card(card)?;
+                self.emit(player_id, GameEventKind::CardPlayed { card_id: *card_id });
+                self.check_announcement(player_id)?;
+            }
+            Action::PlayCards(card_ids) => {
+                // only the first card has to match, the others share its rank
+                for card_id in card_ids {
+                    let card: Card = (*card_id).try_into()?;
+                    self.player_mut(player_id)
+                        .ok_or(DoActionError::PlayerNotFound(player_id))?
+                        .remove_card(&card)?;
+                    self.discard_pile.push(card);
+                    self.emit(player_id, GameEventKind::CardPlayed { card_id: *card_id });
+                }
+                self.check_announcement(player_id)?;
+            }
+            Action::DrawCards(n) => {
+                drawn_cards = self.draw_many_cards(player_id, *n as usize)?;
+            }
+            Action::DecideSuit(suit) => {
+                self.emit(player_id, GameEventKind::SuitWished { suit: suit.clone() });
+            }
+            Action::CannotPlay => {
+                self.emit(player_id, GameEventKind::TurnEnded);
+            }
+            Action::CallMau | Action::CallMauMau => {
+                let announcement = match action {
+                    Action::CallMau => Announcement::Mau,
+                    _ => Announcement::MauMau,
+                };
+                self.announcements.push(MauCall {
+                    player_id,
+                    announcement,
+                });
+                self.emit(player_id, GameEventKind::Announced { announcement });
+            }
+            Action::ChallengeMau(challenged_player_id) => {
+                self.missed_mau = None;
+                self.emit(
+                    player_id,
+                    GameEventKind::MauChallenged {
+                        challenged_player_id: *challenged_player_id,
+                    },
+                );
+                let penalty = self.rules.mau_penalty.unwrap_or_default();
+                self.draw_many_cards(*challenged_player_id, penalty as usize)?;
+            }
+            Action::ReshuffleDeck => {}
+        }
+        if self.missed_mau.is_some_and(|missed| missed != player_id) && !is_challenge {
+            // the challenge window closes once another player has acted
+            self.missed_mau = None;
+        }
+        self.actions.push(PlayerAction {
+            action: action.clone(),
+            player_id,
+        });
+        let previous_player = self.current_turn_player;
+        self.transition(&action, &drawn_cards);
+        self.forget_outdated_announcements(previous_player);
+        debug_assert_eq!(self.check_invariants(), Ok(()));
+        Ok(self.events[first_event..].to_vec())
+    }
+
+    /// The most specific reason why `action` was rejected: a card that is
+    /// not in the hand or does not fit, or else the turn state it does not
+    /// fit into.
+    fn explain_not_viable(&self, player_id: i64, action: Action) -> DoActionError {
+        let Some(player) = self
+            .players
+            .iter()
+            .find(|player| player.lobby_player.user_id == player_id)
+        else {
+            return DoActionError::PlayerNotFound(player_id);
+        };
+        for card_id in action.played_cards() {
+            let card = match Card::try_from(*card_id) {
+                Ok(card) => card,
+                Err(err) => return err.into(),
+            };
+            if !player.hand.contains(card.id) {
+                return PlayerError::CardNotInHand(card).into();
+            }
+        }
+        if let (Some(lead), TurnState::AwaitingPlay) =
+            (action.played_cards().first(), &self.turn_state)
+        {
+            let card = Card::try_from(*lead).expect("checked above");
+            if let Err(err) = self.check_playable(&card) {
+                return err.into();
+            }
+        }
+        DoActionError::ActionNotViable {
+            action,
+            turn_state: self.turn_state_for(player_id),
+        }
+    }
+
+    /// Penalizes a player who went down to one card or out without the
+    /// matching announcement. A missed "Mau" has to be challenged by an
+    /// opponent, a missed "Mau-Mau" is penalized right away and the player
+    /// stays in the game.
+    fn check_announcement(&mut self, player_id: i64) -> Result<(), DrawCardError> {
+        let penalty = match self.rules.mau_penalty {
+            Some(penalty) => penalty,
+            None => return Ok(()),
+        };
+        let hand_size = self
+            .players
+            .iter()
+            .find(|player| player.lobby_player.user_id == player_id)
+            .map(|player| player.hand.len());
+        match hand_size {
+            Some(1) if !self.has_announced(player_id, Announcement::Mau) => {
+                self.missed_mau = Some(player_id);
+            }
+            Some(0) if !self.has_announced(player_id, Announcement::MauMau) => {
+                self.draw_many_cards(player_id, penalty as usize)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Announcements are made for the hand left after the play of a turn.
+    /// They stay up while that hand is left, until the next turn of their
+    /// player begins.
+    fn forget_outdated_announcements(&mut self, previous_player: i64) {
+        let current_player = self.current_turn_player;
+        let new_turn = current_player != previous_player;
+        let players = &self.players;
+        self.announcements.retain(|call| {
+            if call.player_id == current_player {
+                return !new_turn;
+            }
+            let hand_size = players
+                .iter()
+                .find(|player| player.lobby_player.user_id == call.player_id)
+                .map(|player| player.hand.len());
+            match call.announcement {
+                Announcement::Mau => hand_size == Some(1),
+                Announcement::MauMau => hand_size == Some(0),
+            }
+        });
+        if let Some(missed) = self.missed_mau {
+            let still_one_card = self
+                .players
+                .iter()
+                .any(|player| player.lobby_player.user_id == missed && player.hand.len() == 1);
+            if !still_one_card {
+                self.missed_mau = None;
+            }
+        }
+    }
+
+    /// Moves the game into the next [`TurnState`] after `action` was carried
+    /// out by the current player, who drew `drawn_cards` with it. This is the
+    /// only place where card effects decide whose turn it is and what they
+    /// have to do.
+    fn transition(&mut self, action: &Action, drawn_cards: &[u8]) {
+        let player_id = self.current_turn_player;
+        self.turn_state = match (self.turn_state.clone(), action) {
+            (TurnState::GameOver, _) => TurnState::GameOver,
+            (state, Action::PlayCard(_) | Action::PlayCards(_)) => {
+                // effects that add up apply once per card, the others once
+                let card_ids = action.played_cards();
+                let count = u8::try_from(card_ids.len()).unwrap_or(u8::MAX);
+                let card_id = card_ids.last().expect("plays have at least one card");
+                let card = Card::try_from(*card_id).expect("played cards are valid");
+                self.wished_suit = None;
+                self.skipped_players.clear();
+                // reversing goes along with whatever else the rank does, so
+                // penalties and skips already travel the new way
+                if self.rules.is_reverse_card(&card) {
+                    for _ in 0..count {
+                        self.direction = self.direction.reversed();
+                        let direction = self.direction;
+                        self.emit(player_id, GameEventKind::DirectionChanged { direction });
+                    }
+                }
+                let has_finished = self.players.iter().any(|player| {
+                    player.lobby_player.user_id == player_id && player.hand.is_empty()
+                });
+                if has_finished && self.finish_player(player_id) {
+                    TurnState::GameOver
+                } else if self.rules.is_penalty_card(&card) {
+                    let penalty = self
+                        .rules
+                        .seven_penalty
+                        .unwrap_or_default()
+                        .saturating_mul(count);
+                    let pending = match state {
+                        TurnState::MustDrawPenalty { n } => n.saturating_add(penalty),
+                        _ => penalty,
+                    };
+                    self.next_player();
+                    TurnState::MustDrawPenalty { n: pending }
+                } else if self.rules.is_wish_card(&card) && !has_finished {
+                    TurnState::AwaitingSuitChoice
+                } else if self.rules.is_extra_turn_card(&card) && !has_finished {
+                    TurnState::AwaitingPlay
+                } else if self.rules.is_skip_card(&card) {
+                    let skips = self.rules.eight_skips.unwrap_or_default();
+                    for _ in 0..skips.saturating_mul(count) {
+                        self.next_player();
+                        let skipped_player_id = self.current_turn_player;
+                        self.skipped_players.push(skipped_player_id);
+                        self.emit(
+                            player_id,
+                            GameEventKind::PlayerSkipped { skipped_player_id },
+                        );
+                    }
+                    self.next_player();
+                    TurnState::AwaitingPlay
+                } else {
+                    self.next_player();
+                    TurnState::AwaitingPlay
+                }
+            }
+            (TurnState::AwaitingPlay, Action::DrawCards(_)) => match drawn_cards.last() {
+                Some(card_id) => TurnState::MayPlayDrawnCard { card_id: *card_id },
+                // there was nothing left to draw
+                None => {
+                    self.next_player();
+                    TurnState::AwaitingPlay
+                }
+            },
+            // the penalty is paid, the player continues with a regular turn
+            (TurnState::MustDrawPenalty { .. }, Action::DrawCards(_)) => TurnState::AwaitingPlay,
+            (TurnState::AwaitingSuitChoice, Action::DecideSuit(suit)) => {
+                self.wished_suit = Some(suit.clone());
+                // whoever wishes on the opening jack also plays first
+                if !self.opening_card_on_top() {
+                    self.next_player();
+                }
+                TurnState::AwaitingPlay
+            }
+            (TurnState::MayPlayDrawnCard { .. }, Action::CannotPlay) => {
+                self.next_player();
+                TurnState::AwaitingPlay
+            }
+            (state, _) => state,
+        };
+    }
+}
+
+#[derive(Debug)]
+pub enum PlayCardError {
+    /// Neither suit nor rank match the top card of the discard pile.
+    DoesNotMatch { card: Card, top_card: Card },
+    /// A suit was wished and the card is of another one.
+    NotWishedSuit { card: Card, wished_suit: Suit },
+}
+
+impl Display for PlayCardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlayCardError::DoesNotMatch { card, top_card } => write!(
+                f,
+                "the {} matches neither suit nor rank of the {}",
+                card, top_card
+            ),
+            PlayCardError::NotWishedSuit { card, wished_suit } => {
+                write!(f, "the {} is not of the wished suit {}", card, wished_suit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PlayCardError {}
+
+#[derive(Debug)]
+pub enum DrawCardError {
+    PlayerNotFound(i64),
+}
+
+impl Display for DrawCardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DrawCardError::PlayerNotFound(player_id) => {
+                write!(f, "player {} is not in this game", player_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DrawCardError {}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum Action {
+    PlayCard(u8),
+    /// Several cards of the same rank, played in order. The first has to
+    /// match the discard pile and the last ends up on top.
+    PlayCards(Vec<u8>),
+    DrawCards(u8),
+    DecideSuit(Suit),
+    CannotPlay,
+    CallMau,
+    CallMauMau,
+    ChallengeMau(i64),
+    /// Recorded by the game itself when the discard pile is shuffled back
+    /// into the deck, never a player action.
+    ReshuffleDeck,
+}
+
+impl Action {
+    /// Ids of the cards this action puts on the discard pile, in order.
+    pub fn played_cards(&self) -> &[u8] {
+        match self {
+            Action::PlayCard(card_id) => std::slice::from_ref(card_id),
+            Action::PlayCards(card_ids) => card_ids,
+            _ => &[],
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::PlayCard(card_id) => write!(f, "Play card {}", card_id),
+            Action::PlayCards(card_ids) => {
+                let card_ids: Vec<String> = card_ids.iter().map(u8::to_string).collect();
+                write!(f, "Play cards {}", card_ids.join(", "))
+            }
+            Action::DrawCards(n) => write!(f, "Draw {} cards", n),
+            Action::DecideSuit(suit) => write!(f, "Decide suit {}", suit),
+            Action::CannotPlay => write!(f, "End turn"),
+            Action::CallMau => write!(f, "Call Mau"),
+            Action::CallMauMau => write!(f, "Call Mau-Mau"),
+            Action::ChallengeMau(player_id) => write!(f, "Challenge player {}", player_id),
+            Action::ReshuffleDeck => write!(f, "Reshuffle deck"),
+        }
+    }
+}
+
+/// A broken guarantee found by [`Game::check_invariants`].
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum InvariantViolation {
+    /// A card of the decks in play is nowhere to be found.
+    MissingCard { card_id: u8 },
+    /// A card is in more than one place.
+    DuplicateCard { card_id: u8, count: usize },
+    /// A card that is not part of the decks in play showed up.
+    UnexpectedCard { card_id: u8 },
+    /// The turn belongs to someone who is not playing anymore, or never was.
+    CurrentPlayerNotSeated { player_id: i64 },
+}
+
+#[derive(Debug)]
+pub enum DoActionError {
+    GameOver,
+    NotYourTurn {
+        player_id: i64,
+        current_player: i64,
+    },
+    PlayerNotFound(i64),
+    /// The action does not fit the current [`TurnState`].
+    ActionNotViable {
+        action: Action,
+        turn_state: TurnState,
+    },
+    PlayerError(PlayerError),
+    CardError(CardError),
+    PlayCardError(PlayCardError),
+    DrawCardError(DrawCardError),
+}
+
+impl Display for DoActionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DoActionError::GameOver => write!(f, "the game is over"),
+            DoActionError::NotYourTurn {
+                player_id,
+                current_player,
+            } => write!(
+                f,
+                "it is player {}'s turn, not player {}'s",
+                current_player, player_id
+            ),
+            DoActionError::PlayerNotFound(player_id) => {
+                write!(f, "player {} is not in this game", player_id)
+            }
+            DoActionError::ActionNotViable { action, turn_state } => {
+                write!(f, "\"{}\" is not possible while {}", action, turn_state)
+            }
+            DoActionError::PlayerError(err) => err.fmt(f),
+            DoActionError::CardError(err) => err.fmt(f),
+            DoActionError::PlayCardError(err) => err.fmt(f),
+            DoActionError::DrawCardError(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for DoActionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DoActionError::PlayerError(err) => Some(err),
+            DoActionError::CardError(err) => Some(err),
+            DoActionError::PlayCardError(err) => Some(err),
+            DoActionError::DrawCardError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<PlayCardError> for DoActionError {
+    fn from(err: PlayCardError) -> Self {
+        DoActionError::PlayCardError(err)
+    }
+}
+
+impl From<PlayerError> for DoActionError {
+    fn from(err: PlayerError) -> Self {
+        DoActionError::PlayerError(err)
+    }
+}
+
+impl From<CardError> for DoActionError {
+    fn from(err: CardError) -> Self {
+        DoActionError::CardError(err)
+    }
+}
+
+impl From<DrawCardError> for DoActionError {
+    fn from(err: DrawCardError) -> Self {
+        DoActionError::DrawCardError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::{GreedyBot, RandomBot, Strategy},
+        card::Rank,
+        deck::DeckSpec,
+    };
+
+    fn players(n: i64) -> Vec<LobbyPlayer> {
+        (1..=n)
+            .map(|user_id| LobbyPlayer {
+                user_id,
+                username: format!("player {}", user_id),
+                bot: None,
+            })
+            .collect()
+    }
+
+    fn hands(game: &Game) -> Vec<CardSet> {
+        game.players.iter().map(|player| player.hand).collect()
+    }
+
+    /// Plays random moves until the game is over or `max_actions` were taken,
+    /// checking the invariants after every move. Returns the length of the
+    /// action log and the hands after every move.
+    fn play_randomly(game: &mut Game, seed: u64, max_actions: usize) -> Vec<(usize, Vec<CardSet>)> {
+        let mut bot = RandomBot::new(seed);
+        let mut snapshots = vec![];
+        for _ in 0..max_actions {
+            if game.turn_state == TurnState::GameOver {
+                break;
+            }
+            let player_id = game.current_turn_player;
+            let view = game.player_view(player_id).unwrap();
+            let action = bot.choose_action(&view, &view.viable_actions);
+            game.apply(player_id, action).unwrap();
+            assert_eq!(game.check_invariants(), Ok(()));
+            snapshots.push((game.actions.len(), hands(game)));
+        }
+        snapshots
+    }
+
+    fn assert_replays(rules: RuleSet) {
+        for seed in 0..20 {
+            let mut game = Game::new(players(4), 1, 2, rules.clone(), Some(seed), 0);
+            game.start();
+            assert_eq!(game.check_invariants(), Ok(()));
+            let snapshots = play_randomly(&mut game, seed, 300);
+
+            let replayed = game.state_at(game.actions.len()).unwrap();
+            assert_eq!(hands(&replayed), hands(&game));
+            assert_eq!(replayed.discard_pile, game.discard_pile);
+            assert_eq!(replayed.deck_size(), game.deck_size());
+            assert_eq!(replayed.events, game.events);
+            assert_eq!(replayed.turn_state, game.turn_state);
+            assert_eq!(replayed.current_turn_player, game.current_turn_player);
+            assert_eq!(replayed.placements, game.placements);
+            assert_eq!(replayed.check_invariants(), Ok(()));
+
+            for (step, snapshot) in snapshots.iter().step_by(10) {
+                assert_eq!(&hands(&game.state_at(*step).unwrap()), snapshot);
+            }
+        }
+    }
+
+    /// A game where player 1 holds the first of `hands` and is to play on
+    /// `top`, the others holding the rest in seat order.
+    fn arranged(rules: RuleSet, hands: &[Vec<Card>], top: Card) -> Game {
+        let mut game = Game::new(players(hands.len() as i64), 1, 2, rules, Some(0), 0);
+        for (player, hand) in game.players.iter_mut().zip(hands) {
+            player.hand = hand.iter().cloned().collect();
+            game.deck.cards = game.deck.cards - player.hand;
+        }
+        game.deck.cards.remove(top.id);
+        game.discard_pile.push(top);
+        game.current_turn_player = 1;
+        game
+    }
+
+    fn multi_card_rules() -> RuleSet {
+        RuleSet {
+            multi_card_plays: true,
+            ..RuleSet::default()
+        }
+    }
+
+    /// A hand of one king, a different one for every opponent `seat`.
+    fn filler(seat: usize) -> Vec<Card> {
+        vec![Card::new(Suit::ALL[seat].clone(), Rank::King)]
+    }
+
+    #[test]
+    fn replay_matches_live_game_with_default_rules() {
+        assert_replays(RuleSet::default());
+    }
+
+    #[test]
+    fn replay_matches_live_game_with_house_rules() {
+        assert_replays(RuleSet {
+            ace_extra_turn: true,
+            reverse_rank: Some(Rank::Seven),
+            mau_penalty: Some(2),
+            play_until_last: true,
+            deck_exhaustion: DeckExhaustion::AddFreshDeck,
+            starting_player: StartingPlayer::Random,
+            multi_card_plays: true,
+            deck: DeckSpec {
+                copies: 2,
+                ..DeckSpec::default()
+            },
+            ..RuleSet::default()
+        });
+    }
+
+    #[test]
+    fn same_seed_deals_the_same_game() {
+        let mut first = Game::new(players(3), 1, 2, RuleSet::default(), Some(42), 1);
+        let mut second = Game::new(players(3), 1, 3, RuleSet::default(), Some(42), 1);
+        first.start();
+        second.start();
+        assert_eq!(hands(&first), hands(&second));
+        assert_eq!(first.discard_pile, second.discard_pile);
+        assert_eq!(first.current_turn_player, second.current_turn_player);
+    }
+
+    #[test]
+    fn other_seats_never_see_a_drawn_card() {
+        let mut drawn_card_states = 0;
+        for seed in 0..20 {
+            let mut game = Game::new(players(3), 1, 2, RuleSet::default(), Some(seed), 0);
+            game.start();
+            let mut bot = RandomBot::new(seed);
+            while game.turn_state != TurnState::GameOver && game.actions.len() < 300 {
+                let player_id = game.current_turn_player;
+                let view = game.player_view(player_id).unwrap();
+                let action = bot.choose_action(&view, &view.viable_actions);
+                game.apply(player_id, action).unwrap();
+                if matches!(game.turn_state, TurnState::MayPlayDrawnCard { .. }) {
+                    drawn_card_states += 1;
+                }
+                for seat in game.players.iter() {
+                    let other_id = seat.lobby_player.user_id;
+                    if other_id == game.current_turn_player {
+                        continue;
+                    }
+                    let view = game.player_view(other_id).unwrap();
+                    assert_eq!(view.turn_state, game.turn_state.hide_drawn_card());
+                    assert!(!matches!(
+                        view.turn_state,
+                        TurnState::MayPlayDrawnCard { .. }
+                    ));
+                    assert!(view.events.iter().all(|event| {
+                        event.player_id == other_id
+                            || !matches!(event.kind, GameEventKind::CardsDrawn { .. })
+                    }));
+                }
+            }
+        }
+        assert!(drawn_card_states > 0);
+    }
+
+    #[test]
+    fn opponents_see_a_mau_while_the_caller_holds_one_card() {
+        let rules = RuleSet {
+            mau_penalty: Some(2),
+            ..RuleSet::default()
+        };
+        let mut checked = 0;
+        for seed in 0..20 {
+            let mut game = Game::new(players(3), 1, 2, rules.clone(), Some(seed), 0);
+            game.start();
+            // greedy bots announce whenever they can
+            let mut bot = GreedyBot;
+            while game.turn_state != TurnState::GameOver && game.actions.len() < 300 {
+                let player_id = game.current_turn_player;
+                let view = game.player_view(player_id).unwrap();
+                let action = bot.choose_action(&view, &view.viable_actions);
+                game.apply(player_id, action).unwrap();
+                assert_eq!(game.missed_mau, None);
+                for caller in &game.players {
+                    let caller_id = caller.lobby_player.user_id;
+                    if caller_id == game.current_turn_player || caller.hand.len() != 1 {
+                        continue;
+                    }
+                    let opponent_id = game
+                        .players
+                        .iter()
+                        .map(|player| player.lobby_player.user_id)
+                        .find(|user_id| *user_id != caller_id)
+                        .unwrap();
+                    let view = game.player_view(opponent_id).unwrap();
+                    assert!(view.announcements.contains(&MauCall {
+                        player_id: caller_id,
+                        announcement: Announcement::Mau,
+                    }));
+                    checked += 1;
+                }
+            }
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    fn hints_name_the_players_a_seven_or_eight_affects() {
+        let rules = RuleSet {
+            eight_skips: Some(2),
+            reverse_rank: Some(Rank::Seven),
+            ..RuleSet::default()
+        };
+        let mut checked = 0;
+        for seed in 0..20 {
+            let mut game = Game::new(players(4), 1, 2, rules.clone(), Some(seed), 0);
+            game.start();
+            let mut bot = RandomBot::new(seed);
+            while game.turn_state != TurnState::GameOver && game.actions.len() < 300 {
+                let player_id = game.current_turn_player;
+                let view = game.player_view(player_id).unwrap();
+                for action in &view.viable_actions {
+                    let Action::PlayCard(card_id) = action else {
+                        continue;
+                    };
+                    let card = Card::try_from(*card_id).unwrap();
+                    if view.hand.len() < 2 || !matches!(card.rank, Rank::Seven | Rank::Eight) {
+                        continue;
+                    }
+                    let hint = GreedyBot::suggest(&view, std::slice::from_ref(action)).unwrap();
+                    let mut after =
+                        Game::replay(seed, players(4), rules.clone(), 0, &game.actions).unwrap();
+                    after.apply(player_id, action.clone()).unwrap();
+                    let affected = match card.rank {
+                        Rank::Seven => vec![after.current_turn_player],
+                        _ => after.skipped_players.clone(),
+                    };
+                    for opponent in &view.opponents {
+                        assert_eq!(
+                            hint.rationale.contains(&opponent.username),
+                            affected.contains(&opponent.user_id),
+                            "{}",
+                            hint.rationale
+                        );
+                    }
+                    checked += 1;
+                }
+                let action = bot.choose_action(&view, &view.viable_actions);
+                game.apply(player_id, action).unwrap();
+            }
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    fn multi_card_plays_reject_duplicate_ids() {
+        let seven = Card::new(Suit::Hearts, Rank::Seven);
+        let other_seven = Card::new(Suit::Spades, Rank::Seven);
+        let hand = vec![
+            seven.clone(),
+            other_seven.clone(),
+            Card::new(Suit::Hearts, Rank::Nine),
+        ];
+        let mut game = arranged(
+            multi_card_rules(),
+            &[hand, filler(0)],
+            Card::new(Suit::Hearts, Rank::Ten),
+        );
+        let duplicate = Action::PlayCards(vec![seven.id, other_seven.id, other_seven.id]);
+        assert!(game.apply(1, duplicate).is_err());
+        let again = Action::PlayCards(vec![seven.id, seven.id]);
+        assert!(game.apply(1, again).is_err());
+        assert_eq!(game.players[0].hand.len(), 3);
+        assert!(game
+            .apply(1, Action::PlayCards(vec![seven.id, other_seven.id]))
+            .is_ok());
+    }
+
+    #[test]
+    fn multi_card_plays_reject_a_lead_that_does_not_fit() {
+        let seven = Card::new(Suit::Hearts, Rank::Seven);
+        let other_seven = Card::new(Suit::Spades, Rank::Seven);
+        let mut game = arranged(
+            multi_card_rules(),
+            &[vec![seven.clone(), other_seven.clone()], filler(0)],
+            Card::new(Suit::Hearts, Rank::Ten),
+        );
+        let spades_first = Action::PlayCards(vec![other_seven.id, seven.id]);
+        assert!(game.apply(1, spades_first).is_err());
+        let hearts_first = Action::PlayCards(vec![seven.id, other_seven.id]);
+        assert!(game.apply(1, hearts_first).is_ok());
+        assert_eq!(game.discard_pile.last(), Some(&other_seven));
+    }
+
+    #[test]
+    fn two_sevens_add_twice_the_penalty_to_a_pending_one() {
+        let seven = Card::new(Suit::Hearts, Rank::Seven);
+        let other_seven = Card::new(Suit::Spades, Rank::Seven);
+        let hand = vec![
+            seven.clone(),
+            other_seven.clone(),
+            Card::new(Suit::Hearts, Rank::Nine),
+        ];
+        let mut game = arranged(
+            multi_card_rules(),
+            &[hand, filler(0), filler(1)],
+            Card::new(Suit::Clubs, Rank::Seven),
+        );
+        game.turn_state = TurnState::MustDrawPenalty { n: 2 };
+        game.apply(1, Action::PlayCards(vec![seven.id, other_seven.id]))
+            .unwrap();
+        assert_eq!(game.turn_state, TurnState::MustDrawPenalty { n: 6 });
+        assert_eq!(game.current_turn_player, 2);
+    }
+
+    #[test]
+    fn two_eights_skip_two_seats() {
+        let eight = Card::new(Suit::Hearts, Rank::Eight);
+        let other_eight = Card::new(Suit::Spades, Rank::Eight);
+        let hand = vec![
+            eight.clone(),
+            other_eight.clone(),
+            Card::new(Suit::Hearts, Rank::Nine),
+        ];
+        let mut game = arranged(
+            multi_card_rules(),
+            &[hand, filler(0), filler(1), filler(2)],
+            Card::new(Suit::Hearts, Rank::Ten),
+        );
+        game.apply(1, Action::PlayCards(vec![eight.id, other_eight.id]))
+            .unwrap();
+        assert_eq!(game.skipped_players, vec![2, 3]);
+        assert_eq!(game.current_turn_player, 4);
+        assert_eq!(game.turn_state, TurnState::AwaitingPlay);
+    }
+
+    #[test]
+    fn reshuffle_keeps_every_card_below_the_top_one() {
+        let bottom = Card::new(Suit::Clubs, Rank::Nine);
+        let second_to_top = Card::new(Suit::Clubs, Rank::Ten);
+        let top = Card::new(Suit::Hearts, Rank::Queen);
+        let mut game = arranged(RuleSet::default(), &[filler(3), filler(0)], top.clone());
+        game.deck.cards.remove(bottom.id);
+        game.deck.cards.remove(second_to_top.id);
+        game.discard_pile = vec![bottom.clone(), second_to_top.clone(), top.clone()];
+        // the deck runs dry, only the discard pile is left to draw from
+        game.players[1].hand = game.players[1].hand | game.deck.cards;
+        game.deck.cards = CardSet::new();
+
+        game.apply(1, Action::DrawCards(1)).unwrap();
+        assert_eq!(game.discard_pile, vec![top]);
+        assert_eq!(game.players[0].hand.len(), 2);
+        assert_eq!(game.deck.len(), 1);
+        let drawable = game.players[0].hand | game.deck.cards;
+        assert!(drawable.contains(bottom.id));
+        assert!(drawable.contains(second_to_top.id));
+    }
+}
@@ -0,0 +1,14 @@
+//! The Mau-Mau rules engine: cards, decks, house rules and the game state
+//! machine, free of any web framework so bots, simulations and tools can use
+//! it directly.
+#![allow(clippy::module_inception)]
+pub mod bot;
+pub mod card;
+pub mod card_set;
+pub mod deck;
+pub mod event;
+pub mod game;
+pub mod player;
+pub mod rules;
+pub mod scoring;
+pub mod view;
@@ -0,0 +1,74 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{bot::BotKind, card::Card, card_set::CardSet};
+
+/// A seat at the table, taken by a user in a lobby or filled with a bot.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyPlayer {
+    /// Id of the user, bots get ids below -1 so they never clash with users
+    /// or the game itself.
+    pub user_id: i64,
+    pub username: String,
+    /// Strategy playing this seat, `None` for humans.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bot: Option<BotKind>,
+}
+
+pub struct Player {
+    pub lobby_player: LobbyPlayer,
+    pub hand: CardSet,
+}
+
+impl Player {
+    pub fn new(player: LobbyPlayer) -> Self {
+        Self {
+            lobby_player: player,
+            hand: CardSet::new(),
+        }
+    }
+
+    pub fn to_dto(&self) -> PlayerDTO {
+        PlayerDTO {
+            user_id: self.lobby_player.user_id,
+            username: self.lobby_player.username.clone(),
+            hand_size: self.hand.len(),
+        }
+    }
+
+    pub fn remove_card(&mut self, card: &Card) -> Result<(), PlayerError> {
+        if !self.hand.remove(card.id) {
+            return Err(PlayerError::CardNotInHand(card.clone()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct PlayerDTO {
+    pub username: String,
+    pub user_id: i64,
+    pub hand_size: usize,
+}
+
+#[derive(Deserialize)]
+pub struct CreatePlayer {
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub enum PlayerError {
+    CardNotInHand(Card),
+}
+
+impl Display for PlayerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlayerError::CardNotInHand(card) => write!(f, "the {} is not in the hand", card),
+        }
+    }
+}
+
+impl std::error::Error for PlayerError {}
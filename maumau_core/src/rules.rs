@@ -0,0 +1,201 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    card::{Card, Rank, MAX_COPIES},
+    card_set::CardSet,
+    deck::DeckSpec,
+};
+
+/// Largest number of cards a seven or a missing announcement can cost.
+pub const MAX_PENALTY: u8 = 10;
+/// Largest number of players an eight can skip.
+pub const MAX_SKIPS: u8 = 5;
+
+/// House rules a lobby plays with. Every special card effect can be switched
+/// off, and the ones that carry a number can be tuned.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RuleSet {
+    /// Cards the next player has to draw per seven. `None` disables sevens.
+    pub seven_penalty: Option<u8>,
+    /// Whether a seven can be answered with another seven, passing the
+    /// accumulated penalty on.
+    pub seven_stacking: bool,
+    /// Number of players skipped by an eight. `None` disables eights.
+    pub eight_skips: Option<u8>,
+    /// Whether a jack lets its player wish for the next suit.
+    pub jack_wish: bool,
+    /// Whether an ace gives its player another turn.
+    pub ace_extra_turn: bool,
+    /// Rank that reverses the direction of play. `None` disables reversing.
+    pub reverse_rank: Option<Rank>,
+    /// The cards the game is played with.
+    pub deck: DeckSpec,
+    /// Cards drawn for a missing "Mau" or "Mau-Mau" announcement. `None`
+    /// disables announcements.
+    pub mau_penalty: Option<u8>,
+    /// Whether the game goes on after the winner until only one player has
+    /// cards left, giving every player a place.
+    pub play_until_last: bool,
+    /// What happens when a player has to draw more cards than the deck and
+    /// the reshuffled discard pile hold.
+    pub deck_exhaustion: DeckExhaustion,
+    /// Cards dealt to every player.
+    pub hand_size: u8,
+    /// Who plays first.
+    pub starting_player: StartingPlayer,
+    /// Whether a turned up 7, 8 or jack affects the first player as if they
+    /// had been played.
+    pub opening_effects: bool,
+    /// Whether several cards of the same rank can be played at once, their
+    /// penalties, skips and reversals adding up.
+    pub multi_card_plays: bool,
+    /// Whether players can ask for a suggested move. Ranked games switch it
+    /// off.
+    pub hints: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum StartingPlayer {
+    /// A randomly chosen player.
+    Random,
+    /// The dealer, who changes with every game of a lobby.
+    Dealer,
+    /// The player after the dealer.
+    LeftOfDealer,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum DeckExhaustion {
+    /// The round ends without a winner.
+    EndRound,
+    /// Another copy of the deck is shuffled in.
+    AddFreshDeck,
+    /// The player draws what is left and the rest of the draw is skipped.
+    SkipDraw,
+}
+
+impl Display for DeckExhaustion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let policy = match self {
+            DeckExhaustion::EndRound => "the round ends as a draw",
+            DeckExhaustion::AddFreshDeck => "a fresh deck is shuffled in",
+            DeckExhaustion::SkipDraw => "the remaining draw is skipped",
+        };
+        write!(f, "{}", policy)
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self {
+            seven_penalty: Some(2),
+            seven_stacking: true,
+            eight_skips: Some(1),
+            jack_wish: true,
+            ace_extra_turn: false,
+            reverse_rank: None,
+            deck: DeckSpec::default(),
+            mau_penalty: None,
+            play_until_last: false,
+            deck_exhaustion: DeckExhaustion::SkipDraw,
+            hand_size: 5,
+            starting_player: StartingPlayer::LeftOfDealer,
+            opening_effects: true,
+            multi_card_plays: false,
+            hints: true,
+        }
+    }
+}
+
+impl RuleSet {
+    pub fn is_penalty_card(&self, card: &Card) -> bool {
+        card.rank == Rank::Seven && self.seven_penalty.is_some()
+    }
+
+    /// All cards [`RuleSet::is_penalty_card`] holds for.
+    pub fn penalty_cards(&self) -> CardSet {
+        match self.seven_penalty {
+            Some(_) => CardSet::of_rank(&Rank::Seven),
+            None => CardSet::new(),
+        }
+    }
+
+    pub fn is_skip_card(&self, card: &Card) -> bool {
+        card.rank == Rank::Eight && self.eight_skips.is_some()
+    }
+
+    pub fn is_wish_card(&self, card: &Card) -> bool {
+        card.rank == Rank::Jack && self.jack_wish
+    }
+
+    pub fn is_extra_turn_card(&self, card: &Card) -> bool {
+        card.rank == Rank::Ace && self.ace_extra_turn
+    }
+
+    pub fn is_reverse_card(&self, card: &Card) -> bool {
+        self.reverse_rank.as_ref() == Some(&card.rank)
+    }
+
+    /// Checks that a game can be dealt with these rules. Rules coming from
+    /// players have to pass this before they are stored.
+    pub fn validate(&self) -> Result<(), RuleSetError> {
+        if !self.deck.is_valid() {
+            return Err(RuleSetError::InvalidDeckCopies(self.deck.copies));
+        }
+        let bounded = [
+            ("seven penalty", self.seven_penalty, MAX_PENALTY),
+            ("eight skips", self.eight_skips, MAX_SKIPS),
+            ("Mau penalty", self.mau_penalty, MAX_PENALTY),
+        ];
+        for (rule, value, max) in bounded {
+            if let Some(value) = value.filter(|value| *value > max) {
+                return Err(RuleSetError::OutOfRange { rule, value, max });
+            }
+        }
+        if self.hand_size == 0 {
+            return Err(RuleSetError::EmptyHand);
+        }
+        if self.hand_size as usize >= self.deck.len() {
+            return Err(RuleSetError::HandTooLarge(self.hand_size));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum RuleSetError {
+    InvalidDeckCopies(u8),
+    OutOfRange {
+        rule: &'static str,
+        value: u8,
+        max: u8,
+    },
+    EmptyHand,
+    HandTooLarge(u8),
+}
+
+impl Display for RuleSetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleSetError::InvalidDeckCopies(copies) => write!(
+                f,
+                "a game is played with 1 to {} decks, not {}",
+                MAX_COPIES, copies
+            ),
+            RuleSetError::OutOfRange { rule, value, max } => {
+                write!(f, "the {} is at most {}, not {}", rule, max, value)
+            }
+            RuleSetError::EmptyHand => write!(f, "players need at least one card"),
+            RuleSetError::HandTooLarge(hand_size) => write!(
+                f,
+                "a hand of {} cards does not leave a card to turn up",
+                hand_size
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RuleSetError {}
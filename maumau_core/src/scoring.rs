@@ -0,0 +1,208 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    card::{Card, Rank},
+    game::Game,
+    player::LobbyPlayer,
+};
+
+/// Highest number of points a jack, queen, king or ace can be worth.
+pub const MAX_CARD_VALUE: u32 = 100;
+
+/// Penalty points for cards left in a hand at the end of a round. Number
+/// cards count their face value.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CardValues {
+    pub ace: u32,
+    pub king: u32,
+    pub queen: u32,
+    pub jack: u32,
+}
+
+impl Default for CardValues {
+    fn default() -> Self {
+        Self {
+            ace: 11,
+            king: 4,
+            queen: 3,
+            jack: 20,
+        }
+    }
+}
+
+impl CardValues {
+    pub fn value_of(&self, card: &Card) -> u32 {
+        match card.rank {
+            Rank::Ace => self.ace,
+            Rank::King => self.king,
+            Rank::Queen => self.queen,
+            Rank::Jack => self.jack,
+            Rank::Two => 2,
+            Rank::Three => 3,
+            Rank::Four => 4,
+            Rank::Five => 5,
+            Rank::Six => 6,
+            Rank::Seven => 7,
+            Rank::Eight => 8,
+            Rank::Nine => 9,
+            Rank::Ten => 10,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchEnd {
+    /// The match ends after the round in which a player reaches this score.
+    TargetScore(u32),
+    /// The match ends after this many rounds.
+    Rounds(u32),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MatchRules {
+    pub card_values: CardValues,
+    pub end: MatchEnd,
+}
+
+impl MatchRules {
+    /// Checks that a match can be played with these rules. Rules coming
+    /// from players have to pass this before they are stored.
+    pub fn validate(&self) -> Result<(), MatchRulesError> {
+        match self.end {
+            MatchEnd::Rounds(0) | MatchEnd::TargetScore(0) => {
+                return Err(MatchRulesError::EndsBeforeStart)
+            }
+            _ => {}
+        }
+        let values = &self.card_values;
+        if let Some(value) = [values.ace, values.king, values.queen, values.jack]
+            .into_iter()
+            .find(|value| *value > MAX_CARD_VALUE)
+        {
+            return Err(MatchRulesError::CardValueOutOfRange(value));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum MatchRulesError {
+    /// No rounds to play, or a target score every player starts with.
+    EndsBeforeStart,
+    CardValueOutOfRange(u32),
+}
+
+impl Display for MatchRulesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchRulesError::EndsBeforeStart => {
+                write!(f, "a match has at least one round and a target above 0")
+            }
+            MatchRulesError::CardValueOutOfRange(value) => write!(
+                f,
+                "a card is worth at most {} points, not {}",
+                MAX_CARD_VALUE, value
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MatchRulesError {}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self {
+            card_values: CardValues::default(),
+            end: MatchEnd::Rounds(1),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerScore {
+    pub user_id: i64,
+    pub username: String,
+    pub total: u32,
+}
+
+/// Several consecutive games in one lobby. Cards left in the hands are
+/// scored after every round, the lowest total wins the match.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Match {
+    pub rules: MatchRules,
+    pub game_ids: Vec<i64>,
+    pub scores: Vec<PlayerScore>,
+    pub rounds_played: u32,
+}
+
+impl Match {
+    pub fn new(players: &[LobbyPlayer], rules: MatchRules) -> Self {
+        let scores = players
+            .iter()
+            .map(|player| PlayerScore {
+                user_id: player.user_id,
+                username: player.username.clone(),
+                total: 0,
+            })
+            .collect();
+        Self {
+            rules,
+            game_ids: vec![],
+            scores,
+            rounds_played: 0,
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        match self.rules.end {
+            MatchEnd::Rounds(rounds) => self.rounds_played >= rounds,
+            MatchEnd::TargetScore(target) => self.scores.iter().any(|score| score.total >= target),
+        }
+    }
+
+    pub fn is_recorded(&self, game_id: i64) -> bool {
+        self.game_ids
+            .iter()
+            .position(|id| *id == game_id)
+            .is_some_and(|round| round < self.rounds_played as usize)
+    }
+
+    pub fn start_round(&mut self, game_id: i64) {
+        self.game_ids.push(game_id);
+    }
+
+    /// Adds the hand values of a finished round to the totals.
+    pub fn record_round(&mut self, game: &Game) {
+        for player in &game.players {
+            let points: u32 = player
+                .hand
+                .iter()
+                .map(|card| self.rules.card_values.value_of(&card))
+                .fold(0, u32::saturating_add);
+            if let Some(score) = self
+                .scores
+                .iter_mut()
+                .find(|score| score.user_id == player.lobby_player.user_id)
+            {
+                score.total = score.total.saturating_add(points);
+            }
+        }
+        self.rounds_played += 1;
+    }
+
+    /// Players with the lowest total, more than one on a tie.
+    pub fn leaders(&self) -> Vec<&PlayerScore> {
+        let lowest = self.scores.iter().map(|score| score.total).min();
+        self.scores
+            .iter()
+            .filter(|score| Some(score.total) == lowest)
+            .collect()
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    card::Card,
+    card_set::CardSet,
+    event::{GameEvent, GameEventKind},
+    game::{Action, ActiveConstraints, Direction, MauCall, TurnState},
+    player::PlayerDTO,
+    rules::RuleSet,
+};
+
+/// Everything one seat may know about a game: its own hand and what is
+/// public. Only [`Game::player_view`](crate::game::Game::player_view) builds
+/// it, so the other hands and the order of the deck never leave the game
+/// through it.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PlayerView {
+    pub game_id: i64,
+    pub lobby_id: i64,
+    pub player_id: i64,
+    /// Index of this seat in the seating order.
+    pub seat: usize,
+    pub hand: CardSet,
+    /// The other players with the number of cards they hold, in seat order.
+    pub opponents: Vec<PlayerDTO>,
+    /// Cards played face up since the last reshuffle, the top card last.
+    pub discard_pile: Vec<Card>,
+    pub deck_size: usize,
+    pub decks_in_play: u8,
+    pub current_player: i64,
+    pub turn_state: TurnState,
+    /// Wished suit, pending penalty, skips and direction.
+    pub constraints: ActiveConstraints,
+    pub viable_actions: Vec<Action>,
+    pub winner: Option<i64>,
+    pub placements: Vec<i64>,
+    pub announcements: Vec<MauCall>,
+    pub missed_mau: Option<i64>,
+    pub rules: RuleSet,
+    /// The event log, with the cards other players drew left out.
+    pub events: Vec<GameEvent>,
+}
+
+impl PlayerView {
+    pub fn top_card(&self) -> Option<&Card> {
+        self.discard_pile.last()
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.turn_state == TurnState::GameOver
+    }
+
+    /// The opponent who plays after this seat unless someone is skipped.
+    pub fn next_opponent(&self) -> Option<&PlayerDTO> {
+        self.turn_order(self.constraints.direction)
+            .into_iter()
+            .flatten()
+            .next()
+    }
+
+    /// One round of turns after this seat going `direction`, leaving out
+    /// players who finished. `None` stands for this seat, which comes last
+    /// unless it finished too.
+    pub fn turn_order(&self, direction: Direction) -> Vec<Option<&PlayerDTO>> {
+        let num_seats = self.opponents.len() + 1;
+        (1..=num_seats)
+            .map(|step| match direction {
+                Direction::Clockwise => (self.seat + step) % num_seats,
+                Direction::CounterClockwise => (self.seat + num_seats - step) % num_seats,
+            })
+            .map(|seat| match seat.cmp(&self.seat) {
+                std::cmp::Ordering::Less => Some(&self.opponents[seat]),
+                std::cmp::Ordering::Equal => None,
+                std::cmp::Ordering::Greater => Some(&self.opponents[seat - 1]),
+            })
+            .filter(|seat| {
+                let user_id = seat.map_or(self.player_id, |opponent| opponent.user_id);
+                !self.placements.contains(&user_id)
+            })
+            .collect()
+    }
+
+    pub fn opponent(&self, player_id: i64) -> Option<&PlayerDTO> {
+        self.opponents
+            .iter()
+            .find(|opponent| opponent.user_id == player_id)
+    }
+}
+
+/// `event` as `player_id` saw it: draws of other players only show how many
+/// cards were drawn.
+pub fn redact(event: &GameEvent, player_id: i64) -> GameEvent {
+    match &event.kind {
+        GameEventKind::CardsDrawn { card_ids } if event.player_id != player_id => GameEvent {
+            kind: GameEventKind::CardsDrawnFaceDown {
+                num_cards: card_ids.len(),
+            },
+            ..event.clone()
+        },
+        _ => event.clone(),
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt::Display;
+
+use askama::Template;
+use axum::{
+    http::{header, StatusCode},
+    response::{Html, IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::game::{
+    card::CardError, game::DoActionError, game::ParseActionError, rules::RuleSetError,
+    scoring::MatchRulesError,
+};
+
+/// The error every handler and helper returns. It is sent as a JSON problem
+/// details body (RFC 9457), which [`html_errors`] turns into an HTML fragment
+/// for the htmx pages.
+#[derive(Clone, Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub detail: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            detail: detail.into(),
+        }
+    }
+
+    pub fn bad_request(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, detail)
+    }
+
+    pub fn unauthorized() -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "you need to be logged in")
+    }
+
+    pub fn forbidden(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, detail)
+    }
+
+    pub fn not_found(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, detail)
+    }
+
+    pub fn internal(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, detail)
+    }
+
+    pub fn title(&self) -> &'static str {
+        self.status.canonical_reason().unwrap_or("Error")
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.title(), self.detail)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<DoActionError> for ApiError {
+    fn from(err: DoActionError) -> Self {
+        let status = match err {
+            DoActionError::NotYourTurn { .. } => StatusCode::FORBIDDEN,
+            DoActionError::GameOver => StatusCode::CONFLICT,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        Self::new(status, err.to_string())
+    }
+}
+
+impl From<ParseActionError> for ApiError {
+    fn from(err: ParseActionError) -> Self {
+        Self::bad_request(err.to_string())
+    }
+}
+
+impl From<CardError> for ApiError {
+    fn from(err: CardError) -> Self {
+        Self::bad_request(err.to_string())
+    }
+}
+
+impl From<RuleSetError> for ApiError {
+    fn from(err: RuleSetError) -> Self {
+        Self::bad_request(err.to_string())
+    }
+}
+
+impl From<MatchRulesError> for ApiError {
+    fn from(err: MatchRulesError) -> Self {
+        Self::bad_request(err.to_string())
+    }
+}
+
+#[derive(Serialize)]
+struct ProblemDetails<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    title: &'static str,
+    status: u16,
+    detail: &'a str,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ProblemDetails {
+            kind: "about:blank",
+            title: self.title(),
+            status: self.status.as_u16(),
+            detail: &self.detail,
+        };
+        let body = serde_json::to_string(&body).expect("problem details are serializable");
+        let mut response = (
+            self.status,
+            [(header::CONTENT_TYPE, "application/problem+json")],
+            body,
+        )
+            .into_response();
+        // kept around so html_errors can render the same error differently
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+#[derive(Template)]
+#[template(path = "error.html")]
+struct ErrorTemplate<'a> {
+    title: &'a str,
+    detail: &'a str,
+}
+
+/// Response mapper for the htmx routes. Replaces [`ApiError`] bodies with an
+/// HTML fragment that is swapped into the `#errors` element of the page.
+pub async fn html_errors(response: Response) -> Response {
+    let error = match response.extensions().get::<ApiError>() {
+        Some(error) => error.clone(),
+        None => return response,
+    };
+    let fragment = ErrorTemplate {
+        title: error.title(),
+        detail: &error.detail,
+    };
+    match fragment.render() {
+        Ok(fragment) => (
+            error.status,
+            [("HX-Retarget", "#errors"), ("HX-Reswap", "innerHTML")],
+            Html(fragment),
+        )
+            .into_response(),
+        Err(_) => response,
+    }
+}
@@ -0,0 +1,380 @@
+//! Plays many games between bots, without the server, and prints statistics
+//! that help to decide which house rules to play with.
+//!
+//! Run `cargo run --release --bin maumau-sim -- --help` for the options.
+
+use std::{fs, process::ExitCode};
+
+use maumau_core::{
+    bot::{BotKind, Difficulty, Strategy},
+    card::Card,
+    event::GameEventKind,
+    game::{Game, TurnState},
+    player::LobbyPlayer,
+    rules::RuleSet,
+};
+use serde::Serialize;
+
+const USAGE: &str = "\
+Usage: maumau-sim [OPTIONS]
+
+Options:
+  --games <N>       number of games to play [default: 1000]
+  --seed <SEED>     seed of the first game, game i uses SEED + i [default: 0]
+  --bots <BOTS>     comma-separated strategies in seat order, each one of
+                    random, greedy, mc-easy, mc-medium, mc-hard
+                    [default: greedy,greedy,greedy,greedy]
+  --rules <RULES>   rule set as JSON, or the path of a JSON file, in the
+                    format of the lobby API; missing fields keep their
+                    defaults [default: {}]
+  --format <FMT>    json or csv [default: json]
+  -h, --help        print this help";
+
+/// Actions after which a game is called off, e.g. when nobody can play and
+/// the deck is empty.
+const MAX_ACTIONS: usize = 10_000;
+
+enum Format {
+    Json,
+    Csv,
+}
+
+struct Options {
+    games: u64,
+    seed: u64,
+    bots: Vec<BotKind>,
+    rules: RuleSet,
+    format: Format,
+}
+
+fn parse_bot(name: &str) -> Result<BotKind, String> {
+    match name.trim().to_ascii_lowercase().as_str() {
+        "random" => Ok(BotKind::Random),
+        "greedy" => Ok(BotKind::Greedy),
+        "mc-easy" => Ok(BotKind::MonteCarlo(Difficulty::Easy)),
+        "mc-medium" => Ok(BotKind::MonteCarlo(Difficulty::Medium)),
+        "mc-hard" => Ok(BotKind::MonteCarlo(Difficulty::Hard)),
+        _ => Err(format!("unknown bot \"{}\"", name)),
+    }
+}
+
+fn parse_rules(value: &str) -> Result<RuleSet, String> {
+    let json = if value.trim_start().starts_with('{') {
+        value.to_owned()
+    } else {
+        fs::read_to_string(value).map_err(|err| format!("could not read {}: {}", value, err))?
+    };
+    serde_json::from_str(&json).map_err(|err| format!("invalid rules: {}", err))
+}
+
+/// `None` if the help was asked for.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        games: 1000,
+        seed: 0,
+        bots: vec![BotKind::Greedy; 4],
+        rules: RuleSet::default(),
+        format: Format::Json,
+    };
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            return Ok(None);
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", arg))?;
+        match arg.as_str() {
+            "--games" => {
+                options.games = value
+                    .parse()
+                    .map_err(|_| format!("\"{}\" is not a number of games", value))?
+            }
+            "--seed" => {
+                options.seed = value
+                    .parse()
+                    .map_err(|_| format!("\"{}\" is not a seed", value))?
+            }
+            "--bots" => options.bots = value.split(',').map(parse_bot).collect::<Result<_, _>>()?,
+            "--rules" => options.rules = parse_rules(&value)?,
+            "--format" => {
+                options.format = match value.as_str() {
+                    "json" => Format::Json,
+                    "csv" => Format::Csv,
+                    _ => return Err(format!("unknown format \"{}\"", value)),
+                }
+            }
+            _ => return Err(format!("unknown option \"{}\"", arg)),
+        }
+    }
+    if options.bots.len() < 2 {
+        return Err("at least two bots are needed".to_owned());
+    }
+    options
+        .rules
+        .validate()
+        .map_err(|err| format!("invalid rules: {}", err))?;
+    if options.bots.len() * options.rules.hand_size as usize >= options.rules.deck.len() {
+        return Err("not enough cards for all players".to_owned());
+    }
+    Ok(Some(options))
+}
+
+/// What happened in one game.
+struct GameRecord {
+    winner_seat: Option<usize>,
+    /// Seats between the starting player and the winner in clockwise order,
+    /// 0 if the starting player won.
+    winner_offset: Option<usize>,
+    finished: bool,
+    actions: usize,
+    /// Times the turn passed to another player.
+    turns: usize,
+    reshuffles: usize,
+    /// Lengths of the runs of sevens, stacked or played together, before
+    /// someone drew the penalty. Single sevens are runs of length 1.
+    seven_chains: Vec<usize>,
+}
+
+fn play_game(options: &Options, index: u64) -> GameRecord {
+    let seed = options.seed.wrapping_add(index);
+    let players: Vec<LobbyPlayer> = options
+        .bots
+        .iter()
+        .enumerate()
+        .map(|(seat, kind)| LobbyPlayer {
+            user_id: -2 - seat as i64,
+            username: format!("{} {}", kind, seat + 1),
+            bot: Some(*kind),
+        })
+        .collect();
+    let mut strategies: Vec<Box<dyn Strategy + Send>> = options
+        .bots
+        .iter()
+        .enumerate()
+        .map(|(seat, kind)| kind.strategy(seed.wrapping_add(seat as u64)))
+        .collect();
+    let dealer = (index % players.len() as u64) as usize;
+    let num_seats = players.len();
+    let mut game = Game::new(players, 0, 0, options.rules.clone(), Some(seed), dealer);
+    // before an opening eight can skip them
+    let starting_seat = (-2 - game.current_turn_player) as usize;
+    game.start();
+
+    let mut actions = 0;
+    let mut turns = 0;
+    while game.turn_state != TurnState::GameOver && actions < MAX_ACTIONS {
+        let player_id = game.current_turn_player;
+        let seat = (-2 - player_id) as usize;
+        let view = game
+            .player_view(player_id)
+            .expect("the current player is in the game");
+        let action = strategies[seat].choose_action(&view, &view.viable_actions);
+        game.apply(player_id, action)
+            .expect("bots only choose viable actions");
+        actions += 1;
+        if game.current_turn_player != player_id {
+            turns += 1;
+        }
+    }
+
+    let winner_seat = game.winner.map(|player_id| (-2 - player_id) as usize);
+    let mut record = GameRecord {
+        winner_seat,
+        winner_offset: winner_seat.map(|seat| (seat + num_seats - starting_seat) % num_seats),
+        finished: game.turn_state == TurnState::GameOver,
+        actions,
+        turns,
+        reshuffles: 0,
+        seven_chains: vec![],
+    };
+    let mut chain = 0;
+    for event in &game.events {
+        match &event.kind {
+            GameEventKind::DeckReshuffled { .. } => record.reshuffles += 1,
+            GameEventKind::CardPlayed { card_id } => {
+                let is_penalty =
+                    Card::try_from(*card_id).is_ok_and(|card| game.rules.is_penalty_card(&card));
+                if is_penalty {
+                    chain += 1;
+                } else if chain > 0 {
+                    record.seven_chains.push(chain);
+                    chain = 0;
+                }
+            }
+            GameEventKind::CardsDrawn { .. } if chain > 0 => {
+                record.seven_chains.push(chain);
+                chain = 0;
+            }
+            _ => {}
+        }
+    }
+    if chain > 0 {
+        record.seven_chains.push(chain);
+    }
+    record
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SeatStats {
+    seat: usize,
+    bot: String,
+    wins: u64,
+    win_rate: f64,
+}
+
+/// Wins by seating position relative to the starting player, which changes
+/// from game to game, so the advantage of playing first shows separately
+/// from the strength of the bots.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PositionStats {
+    /// 0 for the starting player, 1 for the player after them and so on.
+    offset: usize,
+    wins: u64,
+    win_rate: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Stats {
+    games: u64,
+    /// Games that ended without a winner, see `deckExhaustion`.
+    draws: u64,
+    /// Games called off after too many actions.
+    unfinished: u64,
+    /// Wins of the bot in every seat.
+    seats: Vec<SeatStats>,
+    positions: Vec<PositionStats>,
+    average_actions: f64,
+    average_turns: f64,
+    reshuffles_per_game: f64,
+    /// Share of games in which the discard pile was reshuffled at least once.
+    games_with_reshuffle: f64,
+    /// Runs of at least two sevens per game.
+    seven_chains_per_game: f64,
+    longest_seven_chain: usize,
+}
+
+fn aggregate(options: &Options, records: &[GameRecord]) -> Stats {
+    let games = records.len() as u64;
+    let per_game = |total: usize| total as f64 / games.max(1) as f64;
+    let seats = options
+        .bots
+        .iter()
+        .enumerate()
+        .map(|(seat, kind)| {
+            let wins = records
+                .iter()
+                .filter(|record| record.winner_seat == Some(seat))
+                .count() as u64;
+            SeatStats {
+                seat: seat + 1,
+                bot: kind.to_string(),
+                wins,
+                win_rate: per_game(wins as usize),
+            }
+        })
+        .collect();
+    let positions = (0..options.bots.len())
+        .map(|offset| {
+            let wins = records
+                .iter()
+                .filter(|record| record.winner_offset == Some(offset))
+                .count() as u64;
+            PositionStats {
+                offset,
+                wins,
+                win_rate: per_game(wins as usize),
+            }
+        })
+        .collect();
+    let chains = records
+        .iter()
+        .flat_map(|record| &record.seven_chains)
+        .filter(|length| **length > 1);
+    Stats {
+        games,
+        draws: records
+            .iter()
+            .filter(|record| record.finished && record.winner_seat.is_none())
+            .count() as u64,
+        unfinished: records.iter().filter(|record| !record.finished).count() as u64,
+        seats,
+        positions,
+        average_actions: per_game(records.iter().map(|record| record.actions).sum()),
+        average_turns: per_game(records.iter().map(|record| record.turns).sum()),
+        reshuffles_per_game: per_game(records.iter().map(|record| record.reshuffles).sum()),
+        games_with_reshuffle: per_game(
+            records
+                .iter()
+                .filter(|record| record.reshuffles > 0)
+                .count(),
+        ),
+        seven_chains_per_game: per_game(chains.clone().count()),
+        longest_seven_chain: chains.max().copied().unwrap_or(0),
+    }
+}
+
+fn to_csv(stats: &Stats) -> String {
+    let mut header = vec![
+        "games",
+        "draws",
+        "unfinished",
+        "average_actions",
+        "average_turns",
+        "reshuffles_per_game",
+        "games_with_reshuffle",
+        "seven_chains_per_game",
+        "longest_seven_chain",
+    ]
+    .into_iter()
+    .map(str::to_owned)
+    .collect::<Vec<_>>();
+    let mut row = vec![
+        stats.games.to_string(),
+        stats.draws.to_string(),
+        stats.unfinished.to_string(),
+        format!("{:.3}", stats.average_actions),
+        format!("{:.3}", stats.average_turns),
+        format!("{:.3}", stats.reshuffles_per_game),
+        format!("{:.3}", stats.games_with_reshuffle),
+        format!("{:.3}", stats.seven_chains_per_game),
+        stats.longest_seven_chain.to_string(),
+    ];
+    for seat in &stats.seats {
+        header.push(format!("seat_{}_win_rate", seat.seat));
+        row.push(format!("{:.3}", seat.win_rate));
+    }
+    for position in &stats.positions {
+        header.push(format!("offset_{}_win_rate", position.offset));
+        row.push(format!("{:.3}", position.win_rate));
+    }
+    format!("{}\n{}\n", header.join(","), row.join(","))
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let records: Vec<GameRecord> = (0..options.games)
+        .map(|index| play_game(&options, index))
+        .collect();
+    let stats = aggregate(&options, &records);
+    match options.format {
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&stats).expect("stats are serializable")
+        ),
+        Format::Csv => print!("{}", to_csv(&stats)),
+    }
+    ExitCode::SUCCESS
+}
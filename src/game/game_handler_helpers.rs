@@ -1,28 +1,36 @@
-use axum::{
-    http::StatusCode,
-    response::{IntoResponse, Response},
-};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use crate::{app_state::AppState, auth::user::AuthSession, game::card::Suit};
+use crate::{api_error::ApiError, app_state::AppState, auth::user::AuthSession};
 
 use super::{
-    card::{CardDTO, Rank, JACK_IDS, SEVENS_IDS},
-    game::{Action, CurrentPlayerGameState, Game},
-    player::{Player, PlayerDTO},
+    bot::{GreedyBot, Hint},
+    event::GameEvent,
+    game::{Action, CurrentPlayerGameState, Game, TurnState},
+    lobby::LobbyPlayer,
+    scoring::Match,
+    view,
 };
 
-pub fn create_game(state: Arc<AppState>, lobby_id: i64) -> Result<i64, Response> {
+#[cfg(debug_assertions)]
+use super::game::InvariantViolation;
+
+/// Deals a new game in the lobby. Its seed is always chosen at random, so
+/// players cannot predict the deal; it is only recorded for replays.
+pub fn create_game(state: Arc<AppState>, lobby_id: i64) -> Result<i64, ApiError> {
     let mut games = state.get_games();
     let mut lobbies = state.get_lobbies();
 
     let lobby = match lobbies.iter_mut().find(|lobby| lobby.id == lobby_id) {
         Some(value) => value,
-        None => return Err((StatusCode::NOT_FOUND, "lobby not found").into_response()),
+        None => return Err(ApiError::not_found("lobby not found")),
     };
 
     if lobby.players.len() < 2 {
-        return Err((StatusCode::BAD_REQUEST, "not enough players").into_response());
+        return Err(ApiError::bad_request("not enough players"));
+    }
+
+    if lobby.players.len() * lobby.rules.hand_size as usize >= lobby.rules.deck.len() {
+        return Err(ApiError::bad_request("not enough cards for all players"));
     }
 
     let mut new_game_id: i64 = rand::random();
@@ -31,12 +39,46 @@ pub fn create_game(state: Arc<AppState>, lobby_id: i64) -> Result<i64, Response>
         new_game_id = rand::random();
     }
 
-    let mut game = Game::new(lobby.players.clone(), lobby.id, new_game_id);
+    let start_new_match = match &lobby.current_match {
+        Some(current_match) => current_match.is_over(),
+        None => true,
+    };
+    if start_new_match {
+        lobby.current_match = Some(Match::new(&lobby.players, lobby.match_rules.clone()));
+    }
+    let current_match = lobby
+        .current_match
+        .as_mut()
+        .expect("match was just started");
+    let round_running = current_match
+        .game_ids
+        .last()
+        .is_some_and(|game_id| !current_match.is_recorded(*game_id));
+    if round_running {
+        return Err(ApiError::bad_request("the current round is still running"));
+    }
+    if current_match.scores.len() != lobby.players.len() {
+        return Err(ApiError::bad_request("players changed during the match"));
+    }
+    current_match.start_round(new_game_id);
+
+    let dealer = lobby.dealer % lobby.players.len();
+    lobby.dealer = (dealer + 1) % lobby.players.len();
+    let mut game = Game::new(
+        lobby.players.clone(),
+        lobby.id,
+        new_game_id,
+        lobby.rules.clone(),
+        None,
+        dealer,
+    );
     lobby.running_game = Some(new_game_id);
-    game.give_cards();
-    game.turn_top_card();
+    game.start();
 
     games.push(game);
+    drop(games);
+    drop(lobbies);
+    spawn_bot_turns(state, new_game_id);
     Ok(new_game_id)
 }
 
@@ -44,16 +86,16 @@ pub fn get_game_state(
     auth_session: AuthSession,
     state: Arc<AppState>,
     game_id: i64,
-) -> Result<CurrentPlayerGameState, Response> {
+) -> Result<CurrentPlayerGameState, ApiError> {
     if auth_session.user.is_none() {
-        return Err((StatusCode::UNAUTHORIZED, "unauthorized").into_response());
+        return Err(ApiError::unauthorized());
     }
     let user_id = auth_session.user.unwrap().id;
     {
         let mut games = state.get_games();
         let game = match games.iter_mut().find(|game| game.id == game_id) {
             Some(value) => value,
-            None => return Err((StatusCode::NOT_FOUND, "game not found").into_response()),
+            None => return Err(ApiError::not_found("game not found")),
         };
 
         if !game
@@ -61,150 +103,257 @@ pub fn get_game_state(
             .iter()
             .any(|player| player.lobby_player.user_id == user_id)
         {
-            return Err((StatusCode::BAD_REQUEST, "player not in game").into_response());
+            return Err(ApiError::bad_request("player not in game"));
         }
     }
-    let mut games = state.get_games();
-    let game = match games.iter_mut().find(|game| game.id == game_id) {
+    let games = state.get_games();
+    let game = match games.iter().find(|game| game.id == game_id) {
         Some(value) => value,
-        None => return Err((StatusCode::NOT_FOUND, "game not found").into_response()),
+        None => return Err(ApiError::not_found("game not found")),
     };
-    let player = match game
-        .players
+    let mut game_state = player_game_state(game, user_id)?;
+    game_state.current_match = state
+        .get_lobbies()
         .iter()
-        .find(|player| player.lobby_player.user_id == user_id)
-    {
-        Some(value) => value,
-        None => return Err((StatusCode::BAD_REQUEST, "player not found").into_response()),
+        .find(|lobby| lobby.id == game.lobby_id)
+        .and_then(|lobby| lobby.current_match.clone());
+    Ok(game_state)
+}
+
+/// Returns the state of `game_id` as it was after the first `step` entries of
+/// its action log, seen by the requesting player.
+pub fn get_game_state_at(
+    auth_session: AuthSession,
+    state: Arc<AppState>,
+    game_id: i64,
+    step: usize,
+) -> Result<CurrentPlayerGameState, ApiError> {
+    let user_id = match auth_session.user {
+        Some(user) => user.id,
+        None => return Err(ApiError::unauthorized()),
     };
-    let hand: Vec<CardDTO> = player.hand.iter().map(|card| card.to_dto()).collect();
-    let played_cards: Vec<CardDTO> = game.discard_pile.iter().map(|card| card.to_dto()).collect();
-    let opponents = game
-        .players
-        .iter()
-        .filter(|player| player.lobby_player.user_id != user_id)
-        .map(|player| PlayerDTO {
-            user_id: player.lobby_player.user_id,
-            username: player.lobby_player.username.clone(),
-            hand_size: player.hand.len(),
-        })
-        .collect::<Vec<_>>();
-
-    let my_turn = game.current_turn_player == user_id;
-    let viable_actions = if my_turn {
-        calculate_viable_actions(player, game)
-    } else {
-        vec![]
+    // replaying takes a while, so only what it needs is copied out of the
+    // lock and the game is replayed after releasing it
+    let (seed, players, rules, dealer, actions, lobby_id) = {
+        let games = state.get_games();
+        let game = match games.iter().find(|game| game.id == game_id) {
+            Some(value) => value,
+            None => return Err(ApiError::not_found("game not found")),
+        };
+        if !game
+            .players
+            .iter()
+            .any(|player| player.lobby_player.user_id == user_id)
+        {
+            return Err(ApiError::bad_request("player not in game"));
+        }
+        if step > game.actions.len() {
+            return Err(ApiError::bad_request("step out of range"));
+        }
+        let players: Vec<LobbyPlayer> = game
+            .players
+            .iter()
+            .map(|player| player.lobby_player.clone())
+            .collect();
+        (
+            game.seed,
+            players,
+            game.rules.clone(),
+            game.dealer,
+            game.actions[..step].to_vec(),
+            game.lobby_id,
+        )
+    };
+    let mut replayed = match Game::replay(seed, players, rules, dealer, &actions) {
+        Ok(value) => value,
+        Err(err) => {
+            return Err(ApiError::internal(format!(
+                "could not replay game: {}",
+                err
+            )))
+        }
     };
+    replayed.id = game_id;
+    replayed.lobby_id = lobby_id;
+    player_game_state(&replayed, user_id)
+}
 
-    let game_state = CurrentPlayerGameState {
-        game_id,
-        hand,
-        current_player: game.current_turn_player,
-        played_cards,
-        opponents,
-        winner: game.winner,
-        deck_size: game.deck_size(),
-        viable_actions,
+/// Runs [`Game::check_invariants`] on `game_id` for debugging, returning
+/// every violation found. The violations name the cards involved, so this
+/// is left out of release builds.
+#[cfg(debug_assertions)]
+pub fn check_game_invariants(
+    auth_session: AuthSession,
+    state: Arc<AppState>,
+    game_id: i64,
+) -> Result<Vec<InvariantViolation>, ApiError> {
+    if auth_session.user.is_none() {
+        return Err(ApiError::unauthorized());
+    }
+    let games = state.get_games();
+    let game = match games.iter().find(|game| game.id == game_id) {
+        Some(value) => value,
+        None => return Err(ApiError::not_found("game not found")),
     };
-    Ok(game_state)
+    Ok(game.check_invariants().err().unwrap_or_default())
 }
 
-pub fn calculate_viable_actions(player: &Player, game: &Game) -> Vec<Action> {
-    let playable_cards: Vec<u8> = player
-        .hand
+/// Suggests one of the requesting player's viable actions, unless the rules
+/// of the game switch hints off.
+pub fn get_hint(
+    auth_session: AuthSession,
+    state: Arc<AppState>,
+    game_id: i64,
+) -> Result<Hint, ApiError> {
+    let user_id = match auth_session.user {
+        Some(user) => user.id,
+        None => return Err(ApiError::unauthorized()),
+    };
+    let games = state.get_games();
+    let game = match games.iter().find(|game| game.id == game_id) {
+        Some(value) => value,
+        None => return Err(ApiError::not_found("game not found")),
+    };
+    if !game.rules.hints {
+        return Err(ApiError::forbidden("hints are switched off for this game"));
+    }
+    let view = match game.player_view(user_id) {
+        Some(value) => value,
+        None => return Err(ApiError::bad_request("player not in game")),
+    };
+    match GreedyBot::suggest(&view, &view.viable_actions) {
+        Some(hint) => Ok(hint),
+        None => Err(ApiError::bad_request("there is nothing to do right now")),
+    }
+}
+
+fn player_game_state(game: &Game, user_id: i64) -> Result<CurrentPlayerGameState, ApiError> {
+    match game.player_view(user_id) {
+        Some(view) => Ok(view.into()),
+        None => Err(ApiError::bad_request("player not found")),
+    }
+}
+
+pub fn apply_action(
+    auth_session: AuthSession,
+    state: Arc<AppState>,
+    game_id: i64,
+    action: Action,
+) -> Result<Vec<GameEvent>, ApiError> {
+    let user_id = match auth_session.user {
+        Some(user) => user.id,
+        None => return Err(ApiError::unauthorized()),
+    };
+    let mut games = state.get_games();
+    let game = match games.iter_mut().find(|game| game.id == game_id) {
+        Some(value) => value,
+        None => return Err(ApiError::not_found("game not found")),
+    };
+    let events = game.apply(user_id, action)?;
+    for event in &events {
+        tracing::debug!(game_id, ?event, "game event");
+    }
+    let events = events
         .iter()
-        .filter(|card| game.can_play_card(card))
-        .map(|card| card.id)
+        .map(|event| view::redact(event, user_id))
         .collect();
+    if game.turn_state == TurnState::GameOver {
+        record_round(&state, game);
+    }
+    drop(games);
+    spawn_bot_turns(state, game_id);
+    Ok(events)
+}
 
-    let last_action = game.actions.last().cloned();
+/// Upper bound on the actions bots take in a row before they pause, so bots
+/// that cannot make progress, e.g. with an empty deck, do not hog a thread.
+const MAX_BOT_ACTIONS: usize = 1000;
+/// How long bots pause after [`MAX_BOT_ACTIONS`] before they go on.
+const BOT_PAUSE: Duration = Duration::from_secs(1);
 
-    let viable_actions: Vec<Action> = match last_action
-        .expect("there should always be a last action")
-        .action
-    {
-        Action::PlayCard(card) => {
-            if SEVENS_IDS.contains(&card) {
-                let playable_sevens: Vec<u8> = player
-                    .hand
-                    .iter()
-                    .filter(|card| card.rank == Rank::Seven)
-                    .map(|card| card.id)
-                    .collect();
-                if !playable_sevens.is_empty() {
-                    playable_sevens
-                        .iter()
-                        .map(|card_id| Action::PlayCard(*card_id))
-                        .collect()
-                } else {
-                    let mut num_consecutive_sevens = 0;
-                    for action in game.actions.iter().rev() {
-                        if let Action::PlayCard(n) = action.action {
-                            if SEVENS_IDS.contains(&n) {
-                                num_consecutive_sevens += 1;
-                            } else {
-                                break;
-                            }
-                        }
-                    }
-                    vec![Action::DrawCards(2 * num_consecutive_sevens)]
-                }
-            } else if JACK_IDS.contains(&card) {
-                vec![
-                    Action::DecideSuit(Suit::Hearts),
-                    Action::DecideSuit(Suit::Diamonds),
-                    Action::DecideSuit(Suit::Clubs),
-                    Action::DecideSuit(Suit::Spades),
-                ]
-            }
-            // eights are skipped auto matically
-            else if !playable_cards.is_empty() {
-                playable_cards
-                    .iter()
-                    .map(|card_id| Action::PlayCard(*card_id))
-                    .collect()
-            } else {
-                vec![Action::DrawCards(1)]
-            }
+/// Takes the turns of bot seats of `game_id` on the blocking thread pool, so
+/// bots that think for a while hold up neither the request nor the runtime.
+/// Every call starts a fresh count of [`MAX_BOT_ACTIONS`].
+pub fn spawn_bot_turns(state: Arc<AppState>, game_id: i64) {
+    tokio::task::spawn_blocking(move || {
+        if play_bot_turns(&state, game_id) {
+            tracing::warn!(
+                game_id,
+                "bots pause after {} actions in a row",
+                MAX_BOT_ACTIONS
+            );
+            tokio::spawn(async move {
+                tokio::time::sleep(BOT_PAUSE).await;
+                spawn_bot_turns(state, game_id);
+            });
         }
-        Action::DecideSuit(suit) => {
-            let playable_cards: Vec<u8> = player
-                .hand
+    });
+}
+
+/// Takes the turns of bot seats until it is a human's turn or the game is
+/// over. The games are only locked to look at and to update the game, not
+/// while a bot is thinking. Returns whether the bots stopped because they
+/// took [`MAX_BOT_ACTIONS`] and still have turns to take.
+fn play_bot_turns(state: &AppState, game_id: i64) -> bool {
+    let mut applied = 0;
+    while applied < MAX_BOT_ACTIONS {
+        let (bot, view, step, seed) = {
+            let games = state.get_games();
+            let Some(game) = games.iter().find(|game| game.id == game_id) else {
+                return false;
+            };
+            if game.turn_state == TurnState::GameOver {
+                return false;
+            }
+            let player_id = game.current_turn_player;
+            let bot = game
+                .players
                 .iter()
-                .filter(|card| card.suit == suit)
-                .map(|card| card.id)
-                .collect();
-            if !playable_cards.is_empty() {
-                playable_cards
-                    .iter()
-                    .map(|card_id| Action::PlayCard(*card_id))
-                    .collect()
-            } else {
-                vec![Action::DrawCards(1)]
+                .find(|player| player.lobby_player.user_id == player_id)
+                .and_then(|player| player.lobby_player.bot);
+            let (Some(bot), Some(view)) = (bot, game.player_view(player_id)) else {
+                return false;
+            };
+            if view.viable_actions.is_empty() {
+                return false;
             }
+            let step = game.actions.len();
+            (bot, view, step, game.seed.wrapping_add(step as u64))
+        };
+        let action = bot
+            .strategy(seed)
+            .choose_action(&view, &view.viable_actions);
+
+        let mut games = state.get_games();
+        let Some(game) = games.iter_mut().find(|game| game.id == game_id) else {
+            return false;
+        };
+        if game.actions.len() != step {
+            // someone acted while the bot was thinking, think again
+            continue;
         }
-        Action::DrawCards(_) => {
-            if !playable_cards.is_empty() {
-                playable_cards
-                    .iter()
-                    .map(|card_id| Action::PlayCard(*card_id))
-                    .collect()
-            } else {
-                vec![Action::CannotPlay]
-            }
+        if let Err(err) = game.apply(view.player_id, action) {
+            tracing::error!(game_id, %err, "bot could not act");
+            return false;
         }
-        Action::CannotPlay => {
-            if !playable_cards.is_empty() {
-                playable_cards
-                    .iter()
-                    .map(|card_id| Action::PlayCard(*card_id))
-                    .collect()
-            } else {
-                vec![Action::DrawCards(1)]
-            }
+        applied += 1;
+        if game.turn_state == TurnState::GameOver {
+            record_round(state, game);
         }
-    };
+    }
+    true
+}
 
-    viable_actions
+/// Scores a finished game in the match of its lobby.
+fn record_round(state: &AppState, game: &Game) {
+    let mut lobbies = state.get_lobbies();
+    let current_match = lobbies
+        .iter_mut()
+        .find(|lobby| lobby.id == game.lobby_id)
+        .and_then(|lobby| lobby.current_match.as_mut());
+    if let Some(current_match) = current_match {
+        if current_match.game_ids.contains(&game.id) && !current_match.is_recorded(game.id) {
+            current_match.record_round(game);
+        }
+    }
 }
@@ -1,5 +1,13 @@
 use serde::{Deserialize, Serialize};
 
+pub use maumau_core::player::LobbyPlayer;
+
+use super::{
+    bot::BotKind,
+    rules::RuleSet,
+    scoring::{Match, MatchRules},
+};
+
 #[derive(Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Lobby {
@@ -7,11 +15,32 @@ pub struct Lobby {
     pub name: String,
     pub players: Vec<LobbyPlayer>,
     pub running_game: Option<i64>,
+    pub rules: RuleSet,
+    pub match_rules: MatchRules,
+    pub current_match: Option<Match>,
+    /// Seat index of the dealer of the next game. The deal passes on with
+    /// every game, also from one match to the next.
+    pub dealer: usize,
+}
+
+impl Lobby {
+    /// Whether a match has started and not ended yet. The seats are fixed
+    /// for the whole match, so players and bots only join between matches.
+    pub fn is_match_running(&self) -> bool {
+        self.current_match
+            .as_ref()
+            .is_some_and(|current_match| !current_match.is_over())
+    }
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CreateLobby {
     pub name: String,
+    #[serde(default)]
+    pub rules: RuleSet,
+    #[serde(default)]
+    pub match_rules: MatchRules,
 }
 
 #[derive(Deserialize)]
@@ -20,9 +49,25 @@ pub struct JoinLobby {
     pub lobby_id: i64,
 }
 
-#[derive(Deserialize, Serialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct LobbyPlayer {
-    pub user_id: i64,
-    pub username: String,
+#[derive(Deserialize)]
+pub struct AddBot {
+    pub kind: BotKind,
+}
+
+/// Form representation of [`AddBot`], with the kind as it is displayed.
+#[derive(Deserialize)]
+pub struct AddBotForm {
+    pub kind: String,
+}
+
+impl TryFrom<AddBotForm> for AddBot {
+    type Error = String;
+
+    fn try_from(form: AddBotForm) -> Result<Self, Self::Error> {
+        BotKind::ALL
+            .into_iter()
+            .find(|kind| kind.to_string() == form.kind)
+            .map(|kind| AddBot { kind })
+            .ok_or(form.kind)
+    }
 }
@@ -1,22 +1,27 @@
 use std::sync::Arc;
 
-use crate::{app_state::AppState, auth::user::AuthSession};
-use axum::{
-    http::StatusCode,
-    response::{IntoResponse, Response},
-};
+use crate::{api_error::ApiError, app_state::AppState, auth::user::AuthSession};
 
-use super::lobby::{Lobby, LobbyPlayer};
+use super::{
+    bot::BotKind,
+    lobby::{Lobby, LobbyPlayer},
+    rules::RuleSet,
+    scoring::MatchRules,
+};
 
 pub fn create_lobby(
     auth_session: AuthSession,
     state: Arc<AppState>,
     lobby_name: &str,
-) -> Result<Lobby, Response> {
+    rules: RuleSet,
+    match_rules: MatchRules,
+) -> Result<Lobby, ApiError> {
     if auth_session.user.is_none() {
-        return Err((StatusCode::UNAUTHORIZED, "unauthorized").into_response());
+        return Err(ApiError::unauthorized());
     }
     let user = auth_session.user.unwrap();
+    rules.validate()?;
+    match_rules.validate()?;
     let mut new_lobby_id: i64 = rand::random();
     let mut lobbies = state.lobbies.lock().expect("mutex was poisoned");
     while new_lobby_id < 0 || lobbies.iter().any(|l| l.id == new_lobby_id) {
@@ -28,8 +33,13 @@ pub fn create_lobby(
         players: vec![LobbyPlayer {
             user_id: user.id,
             username: user.username,
+            bot: None,
         }],
         running_game: None,
+        rules,
+        match_rules,
+        current_match: None,
+        dealer: 0,
     };
     lobbies.push(lobby.clone());
     Ok(lobby)
@@ -39,20 +49,136 @@ pub fn join_lobby_helper(
     state: Arc<AppState>,
     lobby_id: i64,
     auth_session: AuthSession,
-) -> Result<i64, Response> {
+) -> Result<i64, ApiError> {
     let mut lobbies = state.get_lobbies();
     let lobby = match lobbies.iter_mut().find(|l| l.id == lobby_id) {
         Some(value) => value,
-        None => return Err((StatusCode::NOT_FOUND, "Not Found").into_response()),
+        None => return Err(ApiError::not_found("Not Found")),
     };
     let user = match auth_session.user {
         Some(value) => value,
-        None => return Err((StatusCode::UNAUTHORIZED, "Unauthorized").into_response()),
+        None => return Err(ApiError::unauthorized()),
     };
+    if lobby.is_match_running() {
+        return Err(ApiError::bad_request(
+            "players can only join between matches",
+        ));
+    }
     lobby.players.push(LobbyPlayer {
         user_id: user.id,
         username: user.username,
+        bot: None,
     });
     let lobby_id = lobby.id;
     Ok(lobby_id)
 }
+
+/// Fills a seat of the lobby with a bot playing `kind`. Only the lobby owner
+/// can add bots.
+pub fn add_bot(
+    state: Arc<AppState>,
+    lobby_id: i64,
+    auth_session: AuthSession,
+    kind: BotKind,
+) -> Result<Lobby, ApiError> {
+    let mut lobbies = state.get_lobbies();
+    let lobby = match lobbies.iter_mut().find(|l| l.id == lobby_id) {
+        Some(value) => value,
+        None => return Err(ApiError::not_found("Not Found")),
+    };
+    let user = match auth_session.user {
+        Some(value) => value,
+        None => return Err(ApiError::unauthorized()),
+    };
+    if lobby.players[0].user_id != user.id {
+        return Err(ApiError::forbidden("only the lobby owner can add bots"));
+    }
+    if lobby.is_match_running() {
+        return Err(ApiError::bad_request("bots can only join between matches"));
+    }
+    let bot_id = (2..)
+        .map(|n: i64| -n)
+        .find(|id| !lobby.players.iter().any(|player| player.user_id == *id))
+        .expect("a lobby has fewer than i64::MAX players");
+    lobby.players.push(LobbyPlayer {
+        user_id: bot_id,
+        username: format!("{} bot {}", kind, -bot_id - 1),
+        bot: Some(kind),
+    });
+    Ok(lobby.clone())
+}
+
+/// Ends the running match without a result, e.g. when a round was abandoned,
+/// so the lobby can start over. Only the lobby owner can abort a match.
+pub fn abort_match(
+    state: Arc<AppState>,
+    lobby_id: i64,
+    auth_session: AuthSession,
+) -> Result<Lobby, ApiError> {
+    let mut lobbies = state.get_lobbies();
+    let lobby = match lobbies.iter_mut().find(|l| l.id == lobby_id) {
+        Some(value) => value,
+        None => return Err(ApiError::not_found("Not Found")),
+    };
+    let user = match auth_session.user {
+        Some(value) => value,
+        None => return Err(ApiError::unauthorized()),
+    };
+    if lobby.players[0].user_id != user.id {
+        return Err(ApiError::forbidden(
+            "only the lobby owner can abort a match",
+        ));
+    }
+    if !lobby.is_match_running() {
+        return Err(ApiError::bad_request("no match is running"));
+    }
+    lobby.current_match = None;
+    lobby.running_game = None;
+    Ok(lobby.clone())
+}
+
+pub fn update_lobby_rules(
+    state: Arc<AppState>,
+    lobby_id: i64,
+    auth_session: AuthSession,
+    rules: RuleSet,
+) -> Result<Lobby, ApiError> {
+    let mut lobbies = state.get_lobbies();
+    let lobby = match lobbies.iter_mut().find(|l| l.id == lobby_id) {
+        Some(value) => value,
+        None => return Err(ApiError::not_found("Not Found")),
+    };
+    let user = match auth_session.user {
+        Some(value) => value,
+        None => return Err(ApiError::unauthorized()),
+    };
+    if lobby.players[0].user_id != user.id {
+        return Err(ApiError::forbidden("only the lobby owner can change rules"));
+    }
+    rules.validate()?;
+    lobby.rules = rules;
+    Ok(lobby.clone())
+}
+
+pub fn update_lobby_match_rules(
+    state: Arc<AppState>,
+    lobby_id: i64,
+    auth_session: AuthSession,
+    match_rules: MatchRules,
+) -> Result<Lobby, ApiError> {
+    let mut lobbies = state.get_lobbies();
+    let lobby = match lobbies.iter_mut().find(|l| l.id == lobby_id) {
+        Some(value) => value,
+        None => return Err(ApiError::not_found("Not Found")),
+    };
+    let user = match auth_session.user {
+        Some(value) => value,
+        None => return Err(ApiError::unauthorized()),
+    };
+    if lobby.players[0].user_id != user.id {
+        return Err(ApiError::forbidden("only the lobby owner can change rules"));
+    }
+    match_rules.validate()?;
+    lobby.match_rules = match_rules;
+    Ok(lobby.clone())
+}
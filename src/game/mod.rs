@@ -1,8 +1,9 @@
 #![allow(clippy::module_inception)]
-pub mod card;
-pub mod deck;
+pub use maumau_core::{bot, card, card_set, deck, event, player, view};
+
 pub mod game;
 pub mod game_handler_helpers;
 pub mod lobby;
 pub mod lobby_handler_helpers;
-pub mod player;
+pub mod rules;
+pub mod scoring;
@@ -0,0 +1,60 @@
+use serde::Deserialize;
+
+pub use maumau_core::rules::*;
+
+use super::deck::{DeckKind, DeckSpec};
+
+/// Form representation of a [`RuleSet`]. Unchecked checkboxes are not sent at
+/// all and a count of 0 switches the corresponding effect off.
+#[derive(Deserialize)]
+pub struct RuleSetForm {
+    pub seven_penalty: u8,
+    #[serde(default)]
+    pub seven_stacking: bool,
+    pub eight_skips: u8,
+    #[serde(default)]
+    pub jack_wish: bool,
+    #[serde(default)]
+    pub ace_extra_turn: bool,
+    /// Name of the reversing rank as displayed, empty to disable reversing.
+    pub reverse_rank: String,
+    pub deck_kind: DeckKind,
+    pub deck_copies: u8,
+    pub mau_penalty: u8,
+    #[serde(default)]
+    pub play_until_last: bool,
+    pub deck_exhaustion: DeckExhaustion,
+    pub hand_size: u8,
+    pub starting_player: StartingPlayer,
+    #[serde(default)]
+    pub opening_effects: bool,
+    #[serde(default)]
+    pub multi_card_plays: bool,
+    #[serde(default)]
+    pub hints: bool,
+}
+
+impl From<RuleSetForm> for RuleSet {
+    fn from(form: RuleSetForm) -> Self {
+        Self {
+            seven_penalty: Some(form.seven_penalty).filter(|n| *n > 0),
+            seven_stacking: form.seven_stacking,
+            eight_skips: Some(form.eight_skips).filter(|n| *n > 0),
+            jack_wish: form.jack_wish,
+            ace_extra_turn: form.ace_extra_turn,
+            reverse_rank: form.reverse_rank.parse().ok(),
+            deck: DeckSpec {
+                kind: form.deck_kind,
+                copies: form.deck_copies,
+            },
+            mau_penalty: Some(form.mau_penalty).filter(|n| *n > 0),
+            play_until_last: form.play_until_last,
+            deck_exhaustion: form.deck_exhaustion,
+            hand_size: form.hand_size,
+            starting_player: form.starting_player,
+            opening_effects: form.opening_effects,
+            multi_card_plays: form.multi_card_plays,
+            hints: form.hints,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use serde::Deserialize;
+
+pub use maumau_core::scoring::*;
+
+/// Form representation of [`MatchRules`].
+#[derive(Deserialize)]
+pub struct MatchRulesForm {
+    pub ace: u32,
+    pub king: u32,
+    pub queen: u32,
+    pub jack: u32,
+    pub end: String,
+    pub limit: u32,
+}
+
+impl From<MatchRulesForm> for MatchRules {
+    fn from(form: MatchRulesForm) -> Self {
+        let end = match form.end.as_str() {
+            "score" => MatchEnd::TargetScore(form.limit),
+            _ => MatchEnd::Rounds(form.limit.max(1)),
+        };
+        Self {
+            card_values: CardValues {
+                ace: form.ace,
+                king: form.king,
+                queen: form.queen,
+                jack: form.jack,
+            },
+            end,
+        }
+    }
+}
@@ -1,7 +1,13 @@
 use askama::Template;
 use serde::Deserialize;
 
-use crate::game::{card::CardDTO, game::Action, player::PlayerDTO};
+use crate::game::{
+    card::{CardDTO, Rank, Suit},
+    game::{Action, ActiveConstraints},
+    player::PlayerDTO,
+    rules::DeckExhaustion,
+    scoring::Match,
+};
 
 #[derive(Template, Debug)]
 #[template(path = "game.html")]
@@ -10,51 +16,105 @@ pub struct GameTemplate {
     other_players: Vec<PlayerDTO>,
     current_turn_player: i64,
     is_my_turn: bool,
-    winner: Option<PlayerDTO>,
+    winner: Option<String>,
+    placements: Vec<String>,
     last_played_card: CardDTO,
     num_cards_in_deck: usize,
     num_cards_played: usize,
     viable_actions: ActionsToDisplay,
     handle_action_route: String,
+    announcements: Vec<String>,
+    lobby_id: i64,
+    is_game_over: bool,
+    current_match: Option<Match>,
+    next_game_id: Option<i64>,
+    constraints: ActiveConstraints,
+    skipped_players: Vec<String>,
+    deck_exhaustion: DeckExhaustion,
+    reverse_rank: Option<Rank>,
+    check_game_changed_route: String,
+    hint_route: Option<String>,
+    abort_match_route: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "hint.html")]
+pub struct HintTemplate {
+    rationale: String,
 }
 
 #[derive(Debug)]
 pub struct ActionsToDisplay {
     pub playable_cards: Vec<u8>,
+    /// Cards that can be ticked to be played together with others of their
+    /// rank.
+    pub multi_play_cards: Vec<u8>,
     pub draw_cards: Option<u8>,
     pub decide_suit: bool,
     pub end_turn: bool,
+    pub call_mau: bool,
+    pub call_mau_mau: bool,
+    pub challenge_mau: Option<i64>,
 }
 
 impl From<Vec<Action>> for ActionsToDisplay {
     fn from(actions: Vec<Action>) -> Self {
         let mut playable_cards = vec![];
+        let mut multi_play_cards = vec![];
         let mut draw_cards = None;
         let mut decide_suit = false;
         let mut end_turn = false;
+        let mut call_mau = false;
+        let mut call_mau_mau = false;
+        let mut challenge_mau = None;
         for action in actions {
             match action {
                 Action::PlayCard(card_id) => playable_cards.push(card_id),
+                Action::PlayCards(card_ids) => {
+                    for card_id in card_ids {
+                        if !multi_play_cards.contains(&card_id) {
+                            multi_play_cards.push(card_id);
+                        }
+                    }
+                }
                 Action::DrawCards(n) => draw_cards = Some(n),
                 Action::DecideSuit(_) => decide_suit = true,
                 Action::CannotPlay => end_turn = true,
+                Action::CallMau => call_mau = true,
+                Action::CallMauMau => call_mau_mau = true,
+                Action::ChallengeMau(player_id) => challenge_mau = Some(player_id),
+                Action::ReshuffleDeck => {}
             }
         }
         Self {
             playable_cards,
+            multi_play_cards,
             draw_cards,
             decide_suit,
             end_turn,
+            call_mau,
+            call_mau_mau,
+            challenge_mau,
         }
     }
 }
 
 #[derive(Deserialize)]
 pub struct HandleActionParams {
-    pub play_card: Option<u8>,
+    /// Card id or compact card code like `H7`.
+    pub play_card: Option<String>,
+    /// Comma-separated ids or codes of several cards of the same rank, in
+    /// the order they are played.
+    pub play_cards: Option<String>,
     pub draw_cards: Option<u8>,
     pub decide_suit: Option<String>,
+    #[serde(default)]
     pub end_turn: bool,
+    #[serde(default)]
+    pub call_mau: bool,
+    #[serde(default)]
+    pub call_mau_mau: bool,
+    pub challenge_mau: Option<i64>,
 }
 
 #[derive(Deserialize)]
@@ -67,36 +127,74 @@ pub mod get {
 
     use askama_axum::IntoResponse;
     use axum::extract::{Path, State};
+    use axum::http::StatusCode;
     use axum::response::Response;
 
-    use crate::game::game_handler_helpers::get_game_state;
-    use crate::{app_state::AppState, auth::user::AuthSession, game::player::PlayerDTO};
+    use crate::api_error::ApiError;
+    use crate::game::game::Announcement;
+    use crate::game::game_handler_helpers::{get_game_state, get_hint};
+    use crate::{app_state::AppState, auth::user::AuthSession};
 
-    use super::GameTemplate;
+    use super::{GameTemplate, HintTemplate};
 
     pub async fn game_handler(
         auth_session: AuthSession,
         Path(game_id): Path<i64>,
         State(state): State<Arc<AppState>>,
     ) -> Response {
-        let current_player_game_state = match get_game_state(auth_session.clone(), state, game_id) {
-            Ok(value) => value,
-            Err(value) => return value,
-        };
+        let current_player_game_state =
+            match get_game_state(auth_session.clone(), state.clone(), game_id) {
+                Ok(value) => value,
+                Err(value) => return value.into_response(),
+            };
+        let user_id = auth_session.user.unwrap().id;
         let current_turn_player = current_player_game_state.current_player;
-        let is_my_turn = current_turn_player == auth_session.user.unwrap().id;
-        let winner: Option<PlayerDTO> = current_player_game_state.winner.map(|winner_id| {
-            let winner = current_player_game_state
+        let is_my_turn = current_turn_player == user_id;
+        let abort_match_route = state
+            .get_lobbies()
+            .iter()
+            .find(|lobby| lobby.id == current_player_game_state.lobby_id)
+            .filter(|lobby| lobby.players[0].user_id == user_id && lobby.is_match_running())
+            .map(|lobby| format!("/lobbies/{}/abort-match", lobby.id));
+        let username_of = |user_id: i64| {
+            current_player_game_state
                 .opponents
                 .iter()
-                .find(|player| player.user_id == winner_id)
-                .unwrap();
-            PlayerDTO {
-                user_id: winner.user_id,
-                username: winner.username.clone(),
-                hand_size: 0,
-            }
-        });
+                .find(|player| player.user_id == user_id)
+                .map_or("You".to_owned(), |player| player.username.clone())
+        };
+        let winner = current_player_game_state.winner.map(username_of);
+        let placements: Vec<String> = current_player_game_state
+            .placements
+            .iter()
+            .map(|user_id| username_of(*user_id))
+            .collect();
+        let announcements = current_player_game_state
+            .announcements
+            .iter()
+            .map(|call| {
+                let username = current_player_game_state
+                    .opponents
+                    .iter()
+                    .find(|player| player.user_id == call.player_id)
+                    .map_or("You", |player| player.username.as_str());
+                match call.announcement {
+                    Announcement::Mau => format!("{} called Mau", username),
+                    Announcement::MauMau => format!("{} called Mau-Mau", username),
+                }
+            })
+            .collect();
+        let skipped_players: Vec<String> = current_player_game_state
+            .constraints
+            .skipped_players
+            .iter()
+            .map(|user_id| username_of(*user_id))
+            .collect();
+        let next_game_id = current_player_game_state
+            .current_match
+            .as_ref()
+            .and_then(|current_match| current_match.game_ids.last().copied())
+            .filter(|last_game_id| *last_game_id != game_id);
         let num_cards_in_deck = current_player_game_state.deck_size;
         let num_cards_played = current_player_game_state.played_cards.len();
 
@@ -106,18 +204,67 @@ pub mod get {
             current_turn_player,
             is_my_turn,
             winner,
+            placements,
             last_played_card: current_player_game_state
                 .played_cards
                 .last()
                 .unwrap()
                 .clone(),
             handle_action_route: format!("/games/{}/handle-action", game_id),
+            hint_route: Some(format!("/games/{}/hint", game_id))
+                .filter(|_| is_my_turn && current_player_game_state.rules.hints),
+            abort_match_route,
+            check_game_changed_route: format!(
+                "/games/{}/changed/{}",
+                game_id,
+                current_player_game_state.events.len()
+            ),
             num_cards_in_deck,
             num_cards_played,
             viable_actions: current_player_game_state.viable_actions.into(),
+            announcements,
+            lobby_id: current_player_game_state.lobby_id,
+            is_game_over: current_player_game_state.game_over,
+            current_match: current_player_game_state.current_match,
+            next_game_id,
+            constraints: current_player_game_state.constraints,
+            skipped_players,
+            deck_exhaustion: current_player_game_state.rules.deck_exhaustion,
+            reverse_rank: current_player_game_state.rules.reverse_rank.clone(),
         };
         game_template.into_response()
     }
+
+    pub async fn hint(
+        auth_session: AuthSession,
+        Path(game_id): Path<i64>,
+        State(state): State<Arc<AppState>>,
+    ) -> Response {
+        match get_hint(auth_session, state, game_id) {
+            Ok(hint) => HintTemplate {
+                rationale: hint.rationale,
+            }
+            .into_response(),
+            Err(value) => value.into_response(),
+        }
+    }
+
+    /// Reloads the page once the game moved past `seen` events, e.g. after
+    /// other players or bots took their turns.
+    pub async fn check_game_changed(
+        Path((game_id, seen)): Path<(i64, usize)>,
+        State(state): State<Arc<AppState>>,
+    ) -> Response {
+        let games = state.get_games();
+        let game = match games.iter().find(|game| game.id == game_id) {
+            Some(value) => value,
+            None => return ApiError::not_found("game not found").into_response(),
+        };
+        if game.events.len() == seen {
+            return StatusCode::OK.into_response();
+        }
+        ([("HX-Redirect", format!("/games/{}", game_id))]).into_response()
+    }
 }
 
 pub mod post {
@@ -128,10 +275,11 @@ pub mod post {
     use axum::response::IntoResponse;
     use axum::Form;
 
+    use crate::api_error::ApiError;
     use crate::app_state::AppState;
     use crate::auth::user::AuthSession;
     use crate::game::game::Action;
-    use crate::game::game_handler_helpers::{calculate_viable_actions, create_game};
+    use crate::game::game_handler_helpers::{apply_action, create_game};
 
     use super::{HandleActionParams, StartGameParams};
 
@@ -141,7 +289,7 @@ pub mod post {
     ) -> impl IntoResponse {
         let new_game_id = match create_game(state, params.lobby_id) {
             Ok(new_game_id) => new_game_id,
-            Err(error_response) => return error_response,
+            Err(error_response) => return error_response.into_response(),
         };
         (
             [("HX-Redirect", format!("/games/{}", new_game_id))],
@@ -156,37 +304,13 @@ pub mod post {
         Path(game_id): Path<i64>,
         Form(action): Form<HandleActionParams>,
     ) -> impl IntoResponse {
-        let mut games = state.get_games();
-        let game = match games.iter_mut().find(|game| game.id == game_id) {
-            Some(value) => value,
-            None => return (StatusCode::NOT_FOUND, "game not found").into_response(),
-        };
-        let user_id = match auth_session.user {
-            Some(user) => user.id,
-            None => return (StatusCode::UNAUTHORIZED, "unauthorized").into_response(),
-        };
-        if game.current_turn_player != user_id {
-            return (StatusCode::FORBIDDEN, "not your turn").into_response();
-        }
-        let player = match game
-            .players
-            .iter()
-            .find(|player| player.lobby_player.user_id == user_id)
-        {
-            Some(value) => value,
-            None => return (StatusCode::BAD_REQUEST, "player not found").into_response(),
-        };
-        let viable_actions = calculate_viable_actions(player, game);
         let action: Action = match action.try_into() {
             Ok(action) => action,
-            Err(_) => return (StatusCode::BAD_REQUEST, "invalid action").into_response(),
+            Err(err) => return ApiError::from(err).into_response(),
         };
-        if !viable_actions.contains(&action) {
-            return (StatusCode::BAD_REQUEST, "invalid action").into_response();
-        }
-        match game.do_action(action, user_id) {
+        match apply_action(auth_session, state, game_id, action) {
             Ok(_) => (),
-            Err(_) => return (StatusCode::BAD_REQUEST, "invalid action").into_response(),
+            Err(value) => return value.into_response(),
         };
         (StatusCode::OK, "action successful").into_response()
     }
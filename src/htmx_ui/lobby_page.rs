@@ -1,6 +1,13 @@
 use askama::Template;
 
-use crate::game::lobby::Lobby;
+use crate::game::{
+    bot::BotKind,
+    card::Rank,
+    deck::DeckKind,
+    lobby::Lobby,
+    rules::{DeckExhaustion, StartingPlayer},
+    scoring::MatchEnd,
+};
 
 #[derive(Template)]
 #[template(path = "lobby.html")]
@@ -10,6 +17,9 @@ pub struct LobbyTemplate {
     is_lobby_owner: bool,
     not_joined: bool,
     check_game_started_route: String,
+    rules_route: String,
+    match_rules_route: String,
+    bots_route: String,
 }
 
 #[derive(Template)]
@@ -27,7 +37,7 @@ pub mod get {
         http::StatusCode,
     };
 
-    use crate::{app_state::AppState, auth::user::AuthSession};
+    use crate::{api_error::ApiError, app_state::AppState, auth::user::AuthSession};
 
     use super::*;
 
@@ -39,11 +49,11 @@ pub mod get {
         let lobbies = state.get_lobbies();
         let lobby = match lobbies.iter().find(|l| l.id == lobby_id) {
             Some(value) => value.clone(),
-            None => return (StatusCode::NOT_FOUND, "Not Found").into_response(),
+            None => return ApiError::not_found("lobby not found").into_response(),
         };
         let user = match auth_session.user {
             Some(value) => value,
-            None => return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response(),
+            None => return ApiError::unauthorized().into_response(),
         };
         let is_lobby_owner = lobby.players[0].user_id == user.id;
         let not_joined = !lobby.players.iter().any(|p| p.user_id == user.id);
@@ -54,6 +64,9 @@ pub mod get {
             is_lobby_owner,
             not_joined,
             check_game_started_route: format!("/lobbies/{}/started", lobby_id),
+            rules_route: format!("/lobbies/{}/rules", lobby_id),
+            match_rules_route: format!("/lobbies/{}/match-rules", lobby_id),
+            bots_route: format!("/lobbies/{}/bots", lobby_id),
         }
         .into_response()
     }
@@ -65,7 +78,7 @@ pub mod get {
         let lobbies = state.get_lobbies();
         let lobby = match lobbies.iter().find(|l| l.id == lobby_id) {
             Some(value) => value.clone(),
-            None => return (StatusCode::NOT_FOUND, "Not Found").into_response(),
+            None => return ApiError::not_found("lobby not found").into_response(),
         };
 
         let players = lobby.players;
@@ -82,7 +95,7 @@ pub mod get {
         let lobbies = state.get_lobbies();
         let lobby = match lobbies.iter().find(|l| l.id == lobby_id) {
             Some(value) => value,
-            None => return (StatusCode::NOT_FOUND, "Not Found").into_response(),
+            None => return ApiError::not_found("lobby not found").into_response(),
         };
         match lobby.running_game {
             Some(game_id) => (
@@ -102,14 +115,83 @@ pub mod post {
         extract::{Path, State},
         http::StatusCode,
         response::{IntoResponse, Redirect},
+        Form,
     };
 
     use crate::{
+        api_error::ApiError,
         app_state::AppState,
         auth::user::AuthSession,
-        game::lobby_handler_helpers::{create_lobby, join_lobby_helper},
+        game::{
+            lobby::{AddBot, AddBotForm},
+            lobby_handler_helpers::{
+                abort_match, add_bot, create_lobby, join_lobby_helper, update_lobby_match_rules,
+                update_lobby_rules,
+            },
+            rules::{RuleSet, RuleSetForm},
+            scoring::{MatchRules, MatchRulesForm},
+        },
     };
 
+    pub async fn update_rules(
+        Path(lobby_id): Path<i64>,
+        State(state): State<Arc<AppState>>,
+        auth_session: AuthSession,
+        Form(rules): Form<RuleSetForm>,
+    ) -> impl IntoResponse {
+        let lobby_id = match update_lobby_rules(state, lobby_id, auth_session, rules.into()) {
+            Ok(value) => value.id,
+            Err(value) => return value.into_response(),
+        };
+        ([("HX-Redirect", format!("/lobbies/{}", lobby_id))]).into_response()
+    }
+
+    pub async fn update_match_rules(
+        Path(lobby_id): Path<i64>,
+        State(state): State<Arc<AppState>>,
+        auth_session: AuthSession,
+        Form(match_rules): Form<MatchRulesForm>,
+    ) -> impl IntoResponse {
+        let lobby_id =
+            match update_lobby_match_rules(state, lobby_id, auth_session, match_rules.into()) {
+                Ok(value) => value.id,
+                Err(value) => return value.into_response(),
+            };
+        ([("HX-Redirect", format!("/lobbies/{}", lobby_id))]).into_response()
+    }
+
+    pub async fn add_bot_handler(
+        Path(lobby_id): Path<i64>,
+        State(state): State<Arc<AppState>>,
+        auth_session: AuthSession,
+        Form(form): Form<AddBotForm>,
+    ) -> impl IntoResponse {
+        let payload = match AddBot::try_from(form) {
+            Ok(value) => value,
+            Err(kind) => {
+                return ApiError::bad_request(format!("\"{}\" is not a kind of bot", kind))
+                    .into_response()
+            }
+        };
+        let lobby_id = match add_bot(state, lobby_id, auth_session, payload.kind) {
+            Ok(value) => value.id,
+            Err(value) => return value.into_response(),
+        };
+        ([("HX-Redirect", format!("/lobbies/{}", lobby_id))]).into_response()
+    }
+
+    pub async fn abort_match_handler(
+        Path(lobby_id): Path<i64>,
+        State(state): State<Arc<AppState>>,
+        auth_session: AuthSession,
+    ) -> impl IntoResponse {
+        let lobby_id = match abort_match(state, lobby_id, auth_session) {
+            Ok(value) => value.id,
+            Err(value) => return value.into_response(),
+        };
+        ([("HX-Redirect", format!("/lobbies/{}", lobby_id))]).into_response()
+    }
+
     pub async fn create_lobby_handler(
         State(state): State<Arc<AppState>>,
         auth_session: AuthSession,
@@ -122,9 +204,11 @@ pub mod post {
             auth_session,
             state,
             format!("{}'s lobby", username).as_str(),
+            RuleSet::default(),
+            MatchRules::default(),
         ) {
             Ok(value) => value,
-            Err(value) => return value,
+            Err(value) => return value.into_response(),
         };
         (
             [("HX-Redirect", format!("/lobbies/{}", lobby.id))],
@@ -140,7 +224,7 @@ pub mod post {
     ) -> impl IntoResponse {
         let lobby_id = match join_lobby_helper(state, lobby_id, auth_session) {
             Ok(value) => value,
-            Err(value) => return value,
+            Err(value) => return value.into_response(),
         };
         ([("HX-Redirect", format!("/lobbies/{}", lobby_id))]).into_response()
     }
@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
 use axum::{
+    middleware::map_response,
     routing::{get, post},
     Router,
 };
 
-use crate::app_state::AppState;
+use crate::{api_error::html_errors, app_state::AppState};
 
 pub mod game_page;
 pub mod index_page;
@@ -18,14 +19,30 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/lobbies", post(lobby_page::post::create_lobby_handler))
         .route("/lobbies/:id/players", get(lobby_page::get::lobby_players))
         .route("/lobbies/:id/players", post(lobby_page::post::join_lobby))
+        .route("/lobbies/:id/bots", post(lobby_page::post::add_bot_handler))
+        .route(
+            "/lobbies/:id/abort-match",
+            post(lobby_page::post::abort_match_handler),
+        )
+        .route("/lobbies/:id/rules", post(lobby_page::post::update_rules))
+        .route(
+            "/lobbies/:id/match-rules",
+            post(lobby_page::post::update_match_rules),
+        )
         .route(
             "/lobbies/:id/started",
             get(lobby_page::get::check_game_started),
         )
         .route("/games/:id", get(game_page::get::game_handler))
+        .route("/games/:id/hint", get(game_page::get::hint))
+        .route(
+            "/games/:id/changed/:seen",
+            get(game_page::get::check_game_changed),
+        )
         .route("/games", post(game_page::post::create_game_handler))
         .route(
             "/games/:id/handle-action",
             post(game_page::post::handle_action),
         )
+        .layer(map_response(html_errors))
 }
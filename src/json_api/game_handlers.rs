@@ -8,14 +8,20 @@ use axum::{
 };
 
 use crate::{
+    api_error::ApiError,
     app_state::AppState,
     auth::user::AuthSession,
     game::{
-        game::{CreateGame, CreateGameResponse, PlayCardPayload},
-        game_handler_helpers::{create_game, get_game_state},
+        game::{Action, CreateGame, CreateGameResponse, PlayCardPayload},
+        game_handler_helpers::{
+            apply_action, create_game, get_game_state, get_game_state_at, get_hint,
+        },
     },
 };
 
+#[cfg(debug_assertions)]
+use crate::game::game_handler_helpers::check_game_invariants;
+
 pub async fn create_game_handler(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<CreateGame>,
@@ -24,7 +30,7 @@ pub async fn create_game_handler(
 
     let new_game_id = match create_game(state, lobby_id) {
         Ok(new_game_id) => new_game_id,
-        Err(error_response) => return error_response,
+        Err(error_response) => return error_response.into_response(),
     };
     (
         StatusCode::CREATED,
@@ -42,7 +48,20 @@ pub async fn get_game_state_handler(
 ) -> Response {
     let game_state = match get_game_state(auth_session, state, game_id) {
         Ok(value) => value,
-        Err(value) => return value,
+        Err(value) => return value.into_response(),
+    };
+
+    Json(game_state).into_response()
+}
+
+pub async fn get_game_state_at_handler(
+    State(state): State<Arc<AppState>>,
+    Path((game_id, step)): Path<(i64, usize)>,
+    auth_session: AuthSession,
+) -> Response {
+    let game_state = match get_game_state_at(auth_session, state, game_id, step) {
+        Ok(value) => value,
+        Err(value) => return value.into_response(),
     };
 
     Json(game_state).into_response()
@@ -54,65 +73,69 @@ pub async fn play_card(
     auth_session: AuthSession,
     Json(payload): Json<PlayCardPayload>,
 ) -> Response {
-    let user_id = match auth_session.user {
-        Some(user) => user.id,
-        None => return (StatusCode::UNAUTHORIZED, "unauthorized").into_response(),
-    };
     let card = match payload.card.to_card() {
         Ok(card) => card,
-        Err(_) => return (StatusCode::BAD_REQUEST, "invalid card").into_response(),
+        Err(err) => return ApiError::from(err).into_response(),
     };
-    {
-        let mut games = state.get_games();
-        let game = games.iter_mut().find(|game| game.id == game_id);
-        let game = match game {
-            Some(game) => game,
-            None => return (StatusCode::NOT_FOUND, "game not found").into_response(),
-        };
-        if game.current_turn_player != user_id {
-            return (StatusCode::BAD_REQUEST, "not your turn").into_response();
-        }
-
-        let player = game
-            .players
-            .iter_mut()
-            .find(|player| player.lobby_player.user_id == user_id);
-        let player = match player {
-            Some(player) => player,
-            None => return (StatusCode::BAD_REQUEST, "player not in game").into_response(),
-        };
-
-        match player.remove_card(&card) {
-            Ok(_) => {}
-            Err(_) => return (StatusCode::BAD_REQUEST, "card not in hand").into_response(),
-        }
-    }
-
-    let mut games = state.get_games();
-    let game = match games.iter_mut().find(|game| game.id == game_id) {
-        Some(game) => game,
-        None => return (StatusCode::NOT_FOUND, "game not found").into_response(),
+    let hand = match get_game_state(auth_session.clone(), state.clone(), game_id) {
+        Ok(game_state) => game_state.hand,
+        Err(value) => return value.into_response(),
     };
-
-    match game.play_card(card) {
+    // a card code names no copy, play whichever copy is held
+    let held_copies: Vec<u8> = hand
+        .iter()
+        .filter(|held| held.suit == card.suit && held.rank == card.rank)
+        .map(|held| held.id)
+        .collect();
+    let card_id = match held_copies.first() {
+        Some(held_id) if !held_copies.contains(&card.id) => *held_id,
+        _ => card.id,
+    };
+    match apply_action(auth_session, state, game_id, Action::PlayCard(card_id)) {
         Ok(_) => {}
-        Err(_) => return (StatusCode::BAD_REQUEST, "cannot play card").into_response(),
+        Err(value) => return value.into_response(),
     };
 
-    let player = game
-        .players
-        .iter()
-        .find(|player| player.lobby_player.user_id == user_id)
-        .unwrap();
+    (StatusCode::OK, "card played").into_response()
+}
 
-    if player.hand.is_empty() {
-        game.winner = Some(user_id);
-    }
+pub async fn do_action(
+    State(state): State<Arc<AppState>>,
+    Path(game_id): Path<i64>,
+    auth_session: AuthSession,
+    Json(action): Json<Action>,
+) -> Response {
+    let events = match apply_action(auth_session, state, game_id, action) {
+        Ok(events) => events,
+        Err(value) => return value.into_response(),
+    };
 
-    game.next_player();
+    Json(events).into_response()
+}
 
-    // // todo: do i want to return more?
-    // // todo: do i want to make game copy and give easy acces methods to gamestate parts?
-    // // todo: add play card test
-    (StatusCode::OK, "card played").into_response()
+#[cfg(debug_assertions)]
+pub async fn get_invariants(
+    State(state): State<Arc<AppState>>,
+    Path(game_id): Path<i64>,
+    auth_session: AuthSession,
+) -> Response {
+    let violations = match check_game_invariants(auth_session, state, game_id) {
+        Ok(violations) => violations,
+        Err(value) => return value.into_response(),
+    };
+
+    Json(violations).into_response()
+}
+
+pub async fn get_hint_handler(
+    State(state): State<Arc<AppState>>,
+    Path(game_id): Path<i64>,
+    auth_session: AuthSession,
+) -> Response {
+    let hint = match get_hint(auth_session, state, game_id) {
+        Ok(value) => value,
+        Err(value) => return value.into_response(),
+    };
+
+    Json(hint).into_response()
 }
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::State,
+    extract::{Path, State},
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
@@ -11,8 +11,13 @@ use crate::{
     app_state::AppState,
     auth::user::AuthSession,
     game::{
-        lobby::{CreateLobby, JoinLobby, Lobby},
-        lobby_handler_helpers::{create_lobby, join_lobby_helper},
+        lobby::{AddBot, CreateLobby, JoinLobby, Lobby},
+        lobby_handler_helpers::{
+            abort_match, add_bot, create_lobby, join_lobby_helper, update_lobby_match_rules,
+            update_lobby_rules,
+        },
+        rules::RuleSet,
+        scoring::MatchRules,
     },
 };
 
@@ -22,9 +27,15 @@ pub async fn create_lobby_handler(
     Json(playload): Json<CreateLobby>,
 ) -> Response {
     let lobby_name = &playload.name;
-    let lobby = match create_lobby(auth_session, state, lobby_name) {
+    let lobby = match create_lobby(
+        auth_session,
+        state,
+        lobby_name,
+        playload.rules,
+        playload.match_rules,
+    ) {
         Ok(value) => value,
-        Err(value) => return value,
+        Err(value) => return value.into_response(),
     };
 
     (StatusCode::CREATED, Json(lobby)).into_response()
@@ -51,8 +62,63 @@ pub async fn join_lobby(
 
     match join_lobby_helper(state, lobby_id, auth_session) {
         Ok(value) => value,
-        Err(value) => return value,
+        Err(value) => return value.into_response(),
     };
 
     (StatusCode::OK, "player joined lobby").into_response()
 }
+
+pub async fn add_bot_handler(
+    State(state): State<Arc<AppState>>,
+    Path(lobby_id): Path<i64>,
+    auth_session: AuthSession,
+    Json(payload): Json<AddBot>,
+) -> Response {
+    let lobby = match add_bot(state, lobby_id, auth_session, payload.kind) {
+        Ok(value) => value,
+        Err(value) => return value.into_response(),
+    };
+
+    (StatusCode::CREATED, Json(lobby)).into_response()
+}
+
+pub async fn abort_match_handler(
+    State(state): State<Arc<AppState>>,
+    Path(lobby_id): Path<i64>,
+    auth_session: AuthSession,
+) -> Response {
+    let lobby = match abort_match(state, lobby_id, auth_session) {
+        Ok(value) => value,
+        Err(value) => return value.into_response(),
+    };
+
+    Json(lobby).into_response()
+}
+
+pub async fn update_rules(
+    State(state): State<Arc<AppState>>,
+    Path(lobby_id): Path<i64>,
+    auth_session: AuthSession,
+    Json(rules): Json<RuleSet>,
+) -> Response {
+    let lobby = match update_lobby_rules(state, lobby_id, auth_session, rules) {
+        Ok(value) => value,
+        Err(value) => return value.into_response(),
+    };
+
+    Json(lobby).into_response()
+}
+
+pub async fn update_match_rules(
+    State(state): State<Arc<AppState>>,
+    Path(lobby_id): Path<i64>,
+    auth_session: AuthSession,
+    Json(match_rules): Json<MatchRules>,
+) -> Response {
+    let lobby = match update_lobby_match_rules(state, lobby_id, auth_session, match_rules) {
+        Ok(value) => value,
+        Err(value) => return value.into_response(),
+    };
+
+    Json(lobby).into_response()
+}
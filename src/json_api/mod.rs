@@ -10,16 +10,39 @@ use axum::{
 use crate::app_state::AppState;
 
 use self::{
-    game_handlers::{create_game_handler, get_game_state_handler, play_card},
-    lobby_handlers::{create_lobby_handler, get_lobbies, join_lobby},
+    game_handlers::{
+        create_game_handler, do_action, get_game_state_at_handler, get_game_state_handler,
+        get_hint_handler, play_card,
+    },
+    lobby_handlers::{
+        abort_match_handler, add_bot_handler, create_lobby_handler, get_lobbies, join_lobby,
+        update_match_rules, update_rules,
+    },
 };
 
 pub fn router() -> Router<Arc<AppState>> {
-    Router::new()
+    let router = Router::new()
         .route("/lobbies", post(create_lobby_handler))
         .route("/lobbies", get(get_lobbies))
         .route("/lobbies/join", post(join_lobby))
+        .route("/lobbies/:lobby_id/bots", post(add_bot_handler))
+        .route("/lobbies/:lobby_id/abort-match", post(abort_match_handler))
+        .route("/lobbies/:lobby_id/rules", post(update_rules))
+        .route("/lobbies/:lobby_id/match-rules", post(update_match_rules))
         .route("/games", post(create_game_handler))
         .route("/games/:game_id", post(get_game_state_handler))
         .route("/games/:game_id/play-card", post(play_card))
+        .route("/games/:game_id/actions", post(do_action))
+        .route(
+            "/games/:game_id/steps/:step",
+            get(get_game_state_at_handler),
+        )
+        .route("/games/:game_id/hint", get(get_hint_handler));
+    // shows every hand, so it only exists in debug builds
+    #[cfg(debug_assertions)]
+    let router = router.route(
+        "/games/:game_id/invariants",
+        get(game_handlers::get_invariants),
+    );
+    router
 }
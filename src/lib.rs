@@ -1,3 +1,4 @@
+pub mod api_error;
 pub mod app_state;
 pub mod auth;
 pub mod db;